@@ -0,0 +1,174 @@
+use super::{
+    splitter_markdown::split_markdown, splitter_recursive::split_recursive,
+    splitter_sentence::split_sentence, splitter_token::split_token,
+};
+use crate::embedding::{ModelNotLoaded, GLOBAL_EMBEDDING_MODEL};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// 文本切分方式，token 以模型 tokenizer 的 token 数计长度，其余以字符数计
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(tag = "method", rename_all = "lowercase")]
+pub enum Splitter {
+    /// 固定 token 窗口，相邻窗口重叠 overlap 个 token
+    Token {
+        #[serde(default = "Splitter::token_size_default")]
+        size: usize,
+        #[serde(default = "Splitter::token_overlap_default")]
+        overlap: usize,
+    },
+    /// 依次按段落、换行、句末标点、空格递归切分后合并
+    Recursive {
+        #[serde(default = "Splitter::size_default")]
+        size: usize,
+        #[serde(default = "Splitter::overlap_default")]
+        overlap: usize,
+    },
+    /// 按 markdown 标题分节，超长的节再递归切分
+    Markdown {
+        #[serde(default = "Splitter::size_default")]
+        size: usize,
+        #[serde(default = "Splitter::overlap_default")]
+        overlap: usize,
+    },
+    /// 按句切分（支持中日文标点）后合并
+    Sentence {
+        #[serde(default = "Splitter::size_default")]
+        size: usize,
+        #[serde(default = "Splitter::overlap_default")]
+        overlap: usize,
+    },
+}
+
+impl Default for Splitter {
+    fn default() -> Self {
+        Self::Recursive {
+            size: Self::size_default(),
+            overlap: Self::overlap_default(),
+        }
+    }
+}
+
+impl Splitter {
+    fn token_size_default() -> usize {
+        256
+    }
+    fn token_overlap_default() -> usize {
+        32
+    }
+    fn size_default() -> usize {
+        512
+    }
+    fn overlap_default() -> usize {
+        64
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Chunk {
+    pub index: usize,
+    pub text: String,
+    // markdown 切分时所属的标题路径，如 "安装 > 依赖"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+}
+
+pub fn split(text: &str, splitter: &Splitter) -> Result<Vec<Chunk>> {
+    let pieces = match splitter {
+        Splitter::Token { size, overlap } => {
+            let m_t = GLOBAL_EMBEDDING_MODEL
+                .get()
+                .ok_or(ModelNotLoaded("embedding model not loaded"))?;
+            split_token(text, &m_t.1, *size, *overlap)?
+                .into_iter()
+                .map(|t| (t, None))
+                .collect::<Vec<(String, Option<String>)>>()
+        }
+        Splitter::Recursive { size, overlap } => split_recursive(text, *size, *overlap)
+            .into_iter()
+            .map(|t| (t, None))
+            .collect(),
+        Splitter::Markdown { size, overlap } => split_markdown(text, *size, *overlap)
+            .into_iter()
+            .map(|(h, t)| (t, h))
+            .collect(),
+        Splitter::Sentence { size, overlap } => split_sentence(text, *size, *overlap)
+            .into_iter()
+            .map(|t| (t, None))
+            .collect(),
+    };
+
+    let chunks = pieces
+        .into_iter()
+        .enumerate()
+        .map(|(index, (text, heading))| Chunk {
+            index,
+            text,
+            heading,
+        })
+        .collect();
+    Ok(chunks)
+}
+
+pub(crate) fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// 按字符数硬切分
+pub(crate) fn hard_split(text: &str, size: usize) -> Vec<String> {
+    let chars = text.chars().collect::<Vec<char>>();
+    chars
+        .chunks(size.max(1))
+        .map(|c| c.iter().collect::<String>())
+        .collect()
+}
+
+/// 将不超过 size 的片段顺序合并为不超过 size 的块，
+/// 相邻块之间保留末尾不超过 overlap 个字符的片段作为重叠
+pub(crate) fn merge_pieces(pieces: Vec<String>, size: usize, overlap: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut current: Vec<String> = vec![];
+    let mut current_len = 0;
+
+    for piece in pieces {
+        let piece_len = char_len(&piece);
+        if current_len + piece_len > size && !current.is_empty() {
+            push_chunk(&mut chunks, &current);
+            while !current.is_empty() && (current_len > overlap || current_len + piece_len > size) {
+                let removed = current.remove(0);
+                current_len -= char_len(&removed);
+            }
+        }
+        current_len += piece_len;
+        current.push(piece);
+    }
+    push_chunk(&mut chunks, &current);
+    chunks
+}
+
+fn push_chunk(chunks: &mut Vec<String>, pieces: &[String]) {
+    let chunk = pieces.concat();
+    let chunk = chunk.trim();
+    if !chunk.is_empty() {
+        chunks.push(chunk.to_string());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{char_len, merge_pieces};
+
+    //cargo test chunk::chunk_splitter::test::test_merge_pieces -- --nocapture
+    #[test]
+    fn test_merge_pieces() {
+        let pieces = vec![
+            "aaaa ".to_string(),
+            "bbbb ".to_string(),
+            "cccc ".to_string(),
+            "dddd".to_string(),
+        ];
+        let r = merge_pieces(pieces, 10, 5);
+        assert_eq!(r, vec!["aaaa bbbb", "bbbb cccc", "cccc dddd"]);
+        assert!(r.iter().all(|c| char_len(c) <= 10));
+    }
+}
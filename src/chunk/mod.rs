@@ -0,0 +1,7 @@
+mod chunk_splitter;
+mod splitter_markdown;
+mod splitter_recursive;
+mod splitter_sentence;
+mod splitter_token;
+
+pub use chunk_splitter::*;
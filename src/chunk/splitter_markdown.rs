@@ -0,0 +1,108 @@
+use super::{chunk_splitter::char_len, splitter_recursive::split_recursive};
+
+struct Section {
+    // 当前节的标题路径
+    headings: Vec<(usize, String)>,
+    text: String,
+}
+
+/// 按标题分节，返回 (标题路径, 文本)，超过 size 的节继续递归切分
+pub fn split_markdown(text: &str, size: usize, overlap: usize) -> Vec<(Option<String>, String)> {
+    let mut chunks = vec![];
+    for section in sections(text) {
+        let heading = match section.headings.is_empty() {
+            true => None,
+            false => Some(
+                section
+                    .headings
+                    .iter()
+                    .map(|(_, title)| title.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(" > "),
+            ),
+        };
+        let body = section.text.trim();
+        if body.is_empty() {
+            continue;
+        }
+        if char_len(body) <= size {
+            chunks.push((heading, body.to_string()));
+            continue;
+        }
+        for piece in split_recursive(body, size, overlap) {
+            chunks.push((heading.clone(), piece));
+        }
+    }
+    chunks
+}
+
+fn sections(text: &str) -> Vec<Section> {
+    let mut sections = vec![];
+    let mut headings: Vec<(usize, String)> = vec![];
+    let mut current = String::new();
+    let mut in_code_block = false;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+        }
+        let heading = match in_code_block {
+            true => None,
+            false => parse_heading(line),
+        };
+        if let Some((level, title)) = heading {
+            sections.push(Section {
+                headings: headings.clone(),
+                text: std::mem::take(&mut current),
+            });
+            while headings.last().is_some_and(|(l, _)| *l >= level) {
+                headings.pop();
+            }
+            headings.push((level, title));
+        }
+        current.push_str(line);
+    }
+    sections.push(Section {
+        headings,
+        text: current,
+    });
+    sections
+}
+
+// 解析 ATX 标题，如 "## 安装"
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let line = line.trim_end();
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') && !rest.starts_with('\t') {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim();
+    Some((level, title.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_markdown;
+
+    //cargo test chunk::splitter_markdown::test::test_split_markdown -- --nocapture
+    #[test]
+    fn test_split_markdown() {
+        let text = "intro\n# 安装\n依赖说明\n## 依赖\n```\n# not heading\n```\n# 使用\nrun it\n";
+        let r = split_markdown(text, 100, 0);
+        println!("{:?}", r);
+        assert_eq!(r.len(), 4);
+        assert_eq!(r[0], (None, "intro".to_string()));
+        assert_eq!(r[1].0, Some("安装".to_string()));
+        assert_eq!(r[2].0, Some("安装 > 依赖".to_string()));
+        assert!(r[2].1.contains("# not heading"));
+        assert_eq!(
+            r[3],
+            (Some("使用".to_string()), "# 使用\nrun it".to_string())
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use super::chunk_splitter::{char_len, hard_split, merge_pieces};
+
+// 由粗到细的分隔符，全部无法满足长度时按字符硬切分
+const SEPARATORS: [&str; 9] = ["\n\n", "\n", "。", "！", "？", ". ", "! ", "? ", " "];
+
+pub fn split_recursive(text: &str, size: usize, overlap: usize) -> Vec<String> {
+    let pieces = split_pieces(text, size, &SEPARATORS);
+    merge_pieces(pieces, size, overlap)
+}
+
+fn split_pieces(text: &str, size: usize, separators: &[&str]) -> Vec<String> {
+    if char_len(text) <= size {
+        return vec![text.to_string()];
+    }
+
+    let sep_idx = match separators.iter().position(|s| text.contains(s)) {
+        Some(idx) => idx,
+        None => return hard_split(text, size),
+    };
+
+    let mut pieces = vec![];
+    // 分隔符保留在片段末尾，合并后可还原原文
+    for part in text.split_inclusive(separators[sep_idx]) {
+        if char_len(part) <= size {
+            pieces.push(part.to_string());
+        } else {
+            pieces.extend(split_pieces(part, size, &separators[sep_idx + 1..]));
+        }
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_recursive;
+    use crate::chunk::chunk_splitter::char_len;
+
+    //cargo test chunk::splitter_recursive::test::test_split_recursive -- --nocapture
+    #[test]
+    fn test_split_recursive() {
+        let text = "第一段第一句。第一段第二句。\n\n第二段只有一句话。\n\nshort";
+        let r = split_recursive(text, 10, 0);
+        println!("{:?}", r);
+        assert!(r.iter().all(|c| char_len(c) <= 10));
+        assert_eq!(r[0], "第一段第一句。");
+        assert_eq!(r.last().unwrap(), "short");
+    }
+
+    //cargo test chunk::splitter_recursive::test::test_split_recursive_hard -- --nocapture
+    #[test]
+    fn test_split_recursive_hard() {
+        let text = "abcdefghijklmnopqrstuvwxyz";
+        let r = split_recursive(text, 10, 0);
+        assert_eq!(r, vec!["abcdefghij", "klmnopqrst", "uvwxyz"]);
+    }
+}
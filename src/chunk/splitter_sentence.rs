@@ -0,0 +1,103 @@
+use super::chunk_splitter::{char_len, hard_split, merge_pieces};
+
+// 中日文句末标点，其后无需空白即可断句
+const CJK_TERMINATORS: [char; 7] = ['。', '！', '？', '；', '…', '｡', '．'];
+// 英文句末标点，其后须为空白或文本结束
+const ASCII_TERMINATORS: [char; 3] = ['.', '!', '?'];
+// 句末标点后紧跟的右引号、右括号归入当前句
+const CLOSING: [char; 10] = ['"', '\'', ')', '”', '’', '」', '』', '）', '》', '】'];
+
+pub fn split_sentence(text: &str, size: usize, overlap: usize) -> Vec<String> {
+    let mut pieces = vec![];
+    for sentence in sentences(text) {
+        if char_len(&sentence) <= size {
+            pieces.push(sentence);
+        } else {
+            pieces.extend(hard_split(&sentence, size));
+        }
+    }
+    merge_pieces(pieces, size, overlap)
+}
+
+/// 断句，保留句末标点及其后的空白，拼接后可还原原文
+pub fn sentences(text: &str) -> Vec<String> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut sentences = vec![];
+    let mut current = String::new();
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        current.push(c);
+        idx += 1;
+
+        let is_end = if CJK_TERMINATORS.contains(&c) || c == '\n' {
+            true
+        } else if ASCII_TERMINATORS.contains(&c) {
+            // 跳过右引号后判断是否为空白或结尾，避免切开 3.14、e.g 等
+            let mut next = idx;
+            while next < chars.len() && CLOSING.contains(&chars[next]) {
+                next += 1;
+            }
+            next >= chars.len() || chars[next].is_whitespace()
+        } else {
+            false
+        };
+
+        if !is_end {
+            continue;
+        }
+        // 连续的句末标点，如 "？！"、"……"
+        while idx < chars.len()
+            && (CJK_TERMINATORS.contains(&chars[idx]) || ASCII_TERMINATORS.contains(&chars[idx]))
+        {
+            current.push(chars[idx]);
+            idx += 1;
+        }
+        while idx < chars.len() && CLOSING.contains(&chars[idx]) {
+            current.push(chars[idx]);
+            idx += 1;
+        }
+        while idx < chars.len() && chars[idx].is_whitespace() {
+            current.push(chars[idx]);
+            idx += 1;
+        }
+        sentences.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+        sentences.push(current);
+    }
+    sentences
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sentences, split_sentence};
+
+    //cargo test chunk::splitter_sentence::test::test_sentences -- --nocapture
+    #[test]
+    fn test_sentences() {
+        let text = "今天天气很好。你去哪里？“我去公园！”Pi is 3.14. Done";
+        let r = sentences(text);
+        println!("{:?}", r);
+        assert_eq!(
+            r,
+            vec![
+                "今天天气很好。",
+                "你去哪里？",
+                "“我去公园！”",
+                "Pi is 3.14. ",
+                "Done"
+            ]
+        );
+        assert_eq!(r.concat(), text);
+    }
+
+    //cargo test chunk::splitter_sentence::test::test_split_sentence -- --nocapture
+    #[test]
+    fn test_split_sentence() {
+        let text = "一二三四。五六七八。九十。";
+        let r = split_sentence(text, 10, 0);
+        assert_eq!(r, vec!["一二三四。五六七八。", "九十。"]);
+    }
+}
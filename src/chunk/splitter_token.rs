@@ -0,0 +1,36 @@
+use anyhow::{Error as E, Result};
+use tokenizers::Tokenizer;
+
+/// 固定 token 窗口切分，按 token 的字节偏移截取原文
+pub fn split_token(
+    text: &str,
+    tokenizer: &Tokenizer,
+    size: usize,
+    overlap: usize,
+) -> Result<Vec<String>> {
+    // 模型 tokenizer 可能配置了截断，切分时需要完整的 token 序列
+    let mut tokenizer = tokenizer.clone();
+    tokenizer.with_truncation(None).map_err(E::msg)?;
+    let encoding = tokenizer.encode(text, false).map_err(E::msg)?;
+    let offsets = encoding.get_offsets();
+
+    let size = size.max(1);
+    let step = size.saturating_sub(overlap).max(1);
+    let mut chunks = vec![];
+    let mut begin = 0;
+    while begin < offsets.len() {
+        let end = (begin + size).min(offsets.len());
+        let (start_byte, _) = offsets[begin];
+        let (_, end_byte) = offsets[end - 1];
+        if let Some(chunk) = text.get(start_byte..end_byte) {
+            if !chunk.trim().is_empty() {
+                chunks.push(chunk.to_string());
+            }
+        }
+        if end == offsets.len() {
+            break;
+        }
+        begin += step;
+    }
+    Ok(chunks)
+}
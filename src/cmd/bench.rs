@@ -0,0 +1,42 @@
+use clap::{Arg, Command};
+
+pub fn new_bench_cmd() -> Command {
+    clap::Command::new("bench")
+        .about("benchmark embedding throughput and latency with synthetic inputs")
+        .args(&[
+            Arg::new("lengths")
+                .long("lengths")
+                .value_name("WORDS")
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(usize))
+                .default_value("16,64,256")
+                .help("words per synthetic input, keep tokens within the model max length"),
+            Arg::new("batch_sizes")
+                .long("batch-sizes")
+                .value_name("SIZES")
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1,8,32"),
+            Arg::new("concurrency")
+                .long("concurrency")
+                .value_name("LEVELS")
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1,4"),
+            Arg::new("iterations")
+                .long("iterations")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("10")
+                .help("calls per concurrent worker"),
+            Arg::new("device")
+                .long("device")
+                .value_name("DEVICE")
+                .help("override model.device, e.g. cpu"),
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("write the json report to the file"),
+        ])
+}
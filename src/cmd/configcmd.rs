@@ -6,6 +6,7 @@ pub fn new_config_cmd() -> Command {
         .about("config")
         .subcommand(config_show_cmd())
         .subcommand(config_generate_default())
+        .subcommand(config_validate_cmd())
 }
 
 fn config_show_cmd() -> Command {
@@ -21,10 +22,19 @@ fn config_generate_default() -> Command {
         .args(&[Arg::new("filepath").value_name("filepath").index(1)])
 }
 
+fn config_validate_cmd() -> Command {
+    clap::Command::new("validate")
+        .about("check config file, exit non-zero when any check fails")
+        .args(&[Arg::new("file")
+            .value_name("file")
+            .index(1)
+            .help("config file, default is --config or ./config.yml")])
+}
+
 fn config_show_info_cmd() -> Command {
-    clap::Command::new("info").about("show info")
+    clap::Command::new("info").about("show runtime environment info")
 }
 
 fn config_show_all_cmd() -> Command {
-    clap::Command::new("all").about("show all ")
+    clap::Command::new("all").about("show effective config and value sources")
 }
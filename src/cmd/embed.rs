@@ -0,0 +1,38 @@
+use clap::{Arg, ArgAction, Command};
+
+pub fn new_embed_cmd() -> Command {
+    clap::Command::new("embed")
+        .about("embed each line of a file or stdin with the configured model")
+        .args(&[
+            Arg::new("input")
+                .value_name("input")
+                .index(1)
+                .help("input file, read stdin if absent or '-'"),
+            Arg::new("jsonl")
+                .long("jsonl")
+                .action(ArgAction::SetTrue)
+                .help("input is jsonl, read text from --text-field"),
+            Arg::new("text_field")
+                .long("text-field")
+                .value_name("FIELD")
+                .help("jsonl text field, default ingest.jsonl_text_field"),
+            Arg::new("format")
+                .short('f')
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["jsonl", "npy", "f32"])
+                .default_value("jsonl")
+                .help("output format, f32 is raw little-endian row-major floats"),
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("output file, write to stdout if absent"),
+            Arg::new("batch_size")
+                .long("batch-size")
+                .value_name("SIZE")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("32")
+                .help("lines embedded per forward"),
+        ])
+}
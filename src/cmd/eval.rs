@@ -0,0 +1,38 @@
+use clap::{Arg, Command};
+
+pub fn new_eval_cmd() -> Command {
+    clap::Command::new("eval")
+        .about("evaluate retrieval with a jsonl of queries and relevant ids")
+        .long_about(
+            "each line of the input is {\"query\": \"...\", \"relevant_ids\": [\"...\"]}, \
+             reports recall@k, MRR, nDCG@k and latency percentiles",
+        )
+        .args(&[
+            Arg::new("file").value_name("file").required(true).index(1),
+            Arg::new("k")
+                .short('k')
+                .value_name("K")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("10"),
+            Arg::new("id_field")
+                .long("id-field")
+                .value_name("FIELD")
+                .help(
+                    "compare relevant ids with this payload field instead of point ids, e.g. path",
+                ),
+            Arg::new("rerank")
+                .long("rerank")
+                .value_name("BOOL")
+                .value_parser(clap::value_parser!(bool))
+                .help("override reranker.enable"),
+            Arg::new("diversify")
+                .long("diversify")
+                .value_name("JSON")
+                .help("diversify options, e.g. '{\"method\": \"mmr\", \"lambda\": 0.5}'"),
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("write the full json report to the file"),
+        ])
+}
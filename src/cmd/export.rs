@@ -0,0 +1,26 @@
+use clap::{Arg, ArgAction, Command};
+
+pub fn new_export_cmd() -> Command {
+    clap::Command::new("export")
+        .about("export ids, payloads and optionally vectors of a collection to jsonl")
+        .args(&[
+            Arg::new("file")
+                .value_name("file")
+                .index(1)
+                .help("output file, write to stdout if absent"),
+            Arg::new("collection")
+                .long("collection")
+                .value_name("COLLECTION")
+                .help("collection to export, default qdrant.collection"),
+            Arg::new("with_vectors")
+                .long("with-vectors")
+                .action(ArgAction::SetTrue)
+                .help("include vectors"),
+            Arg::new("batch_size")
+                .long("batch-size")
+                .value_name("SIZE")
+                .value_parser(clap::value_parser!(u32))
+                .default_value("256")
+                .help("points fetched per scroll request"),
+        ])
+}
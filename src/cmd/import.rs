@@ -0,0 +1,26 @@
+use clap::{Arg, Command};
+
+pub fn new_import_cmd() -> Command {
+    clap::Command::new("import")
+        .about("import a jsonl file, one document per line, with batched embedding")
+        .args(&[
+            Arg::new("file").value_name("file").required(true).index(1),
+            Arg::new("text_field")
+                .long("text-field")
+                .value_name("FIELD")
+                .help("field holding the text to embed, default ingest.jsonl_text_field"),
+            Arg::new("id_field")
+                .long("id-field")
+                .value_name("FIELD")
+                .help("field holding the point id, default ingest.jsonl_id_field"),
+            Arg::new("collection")
+                .long("collection")
+                .value_name("COLLECTION")
+                .help("target collection, default qdrant.collection"),
+            Arg::new("batch_size")
+                .long("batch-size")
+                .value_name("SIZE")
+                .value_parser(clap::value_parser!(usize))
+                .help("documents embedded and upserted per batch, default ingest.batch_size"),
+        ])
+}
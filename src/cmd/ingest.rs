@@ -0,0 +1,33 @@
+use clap::{Arg, ArgAction, Command};
+
+pub fn new_ingest_cmd() -> Command {
+    clap::Command::new("ingest")
+        .about("chunk, embed and upsert text/markdown/jsonl files of a folder")
+        .args(&[
+            Arg::new("folder")
+                .value_name("folder")
+                .required(true)
+                .index(1),
+            Arg::new("include")
+                .long("include")
+                .value_name("REGEX")
+                .action(ArgAction::Append)
+                .help("only ingest files whose path matches the regex"),
+            Arg::new("exclude")
+                .long("exclude")
+                .value_name("REGEX")
+                .action(ArgAction::Append)
+                .help("skip files whose path matches the regex"),
+            Arg::new("modified_after")
+                .long("modified-after")
+                .value_name("TIMESTAMP")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with("modified_before")
+                .help("only ingest files modified after the unix timestamp"),
+            Arg::new("modified_before")
+                .long("modified-before")
+                .value_name("TIMESTAMP")
+                .value_parser(clap::value_parser!(usize))
+                .help("only ingest files modified before the unix timestamp"),
+        ])
+}
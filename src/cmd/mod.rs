@@ -1,9 +1,25 @@
+mod bench;
 mod configcmd;
+mod embed;
+mod eval;
+mod export;
+mod import;
+mod ingest;
 mod rootcmd;
+mod search;
 mod start;
+mod status;
 mod stop;
 
+pub use bench::new_bench_cmd;
 pub use configcmd::new_config_cmd;
+pub use embed::new_embed_cmd;
+pub use eval::new_eval_cmd;
+pub use export::new_export_cmd;
+pub use import::new_import_cmd;
+pub use ingest::new_ingest_cmd;
 pub use rootcmd::run_app;
+pub use search::new_search_cmd;
 pub use start::new_start_cmd;
+pub use status::new_status_cmd;
 pub use stop::new_stop_cmd;
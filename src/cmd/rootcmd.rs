@@ -1,23 +1,51 @@
-use crate::cmd::{new_config_cmd, new_start_cmd, new_stop_cmd};
+use crate::cmd::{
+    new_bench_cmd, new_config_cmd, new_embed_cmd, new_eval_cmd, new_export_cmd, new_import_cmd,
+    new_ingest_cmd, new_search_cmd, new_start_cmd, new_status_cmd, new_stop_cmd,
+};
+use crate::configure::config_reload::{reload_config, watch_config_file};
+use crate::configure::config_source::{inspect_config, GLOBAL_CONFIG_SOURCES};
+use crate::configure::config_validate::validate_config;
 use crate::configure::generate_default_config;
-use crate::configure::{get_config, get_current_config_yml, set_config};
+use crate::configure::{
+    get_config, get_current_config_yml, load_config, set_config, GLOBAL_CONFIG,
+};
+use crate::daemon::{
+    format_uptime, pid_file_locked, process_alive, read_pid, send_signal, state_file_path, PidFile,
+    ServerState,
+};
 
-use crate::embedding::answer::{init_global_pipeline, GLOBAL_PIPELINE};
+use crate::commons::{
+    format_table, struct_to_json_string, table_cell, LastModifyFilter, LastModifyFilterType,
+};
+use crate::embedding::answer::{
+    init_global_pipeline, GLOBAL_PIPELINE, MODEL_ID as GENERATION_MODEL_ID,
+};
+use crate::embedding::embed_file::{EmbedFile, EmbedFormat};
+use crate::embedding::reranker::{init_global_reranker, GLOBAL_RERANKER};
+use crate::embedding::retriever::{retriever, RetrievedPoint, RetrieverOptions};
 use crate::embedding::{init_model_and_tokenizer, GLOBAL_EMBEDDING_MODEL, GLOBAL_RUNTIME};
+use crate::evaluate::{BenchEmbedding, EvalRetriever};
 use crate::httpserver;
+use crate::httpserver::{GLOBAL_SHUTDOWN, GLOBAL_STARTUP};
+use crate::ingest::{watch_folder, ExportJsonl, ImportJsonl, IngestFolder};
+use crate::logger::tracing_init;
 use crate::resources::init_resources;
+use crate::resources::resource_qdrant::point_id_to_string;
+use crate::tasks::{now_secs, TaskProgress, GLOBAL_TASK_MANAGER};
 use clap::{Arg, ArgAction, ArgMatches};
 use fork::{daemon, Fork};
 use lazy_static::lazy_static;
-use signal_hook::consts::{SIGTERM, TERM_SIGNALS};
+use qdrant_client::Payload;
+use signal_hook::consts::{SIGHUP, TERM_SIGNALS};
 use signal_hook::iterator::exfiltrator::WithOrigin;
 use signal_hook::iterator::SignalsInfo;
-use std::net::{self, IpAddr};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::{exit, Command};
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use std::{env, fs, thread};
-use sysinfo::{Pid, RefreshKind, System};
-use tokio::net::TcpListener;
+use sysinfo::System;
 use tokio::runtime::{self};
 
 lazy_static! {
@@ -33,6 +61,14 @@ lazy_static! {
                 .value_name("FILE")
                 .help("Sets a custom config file")
         )
+        .arg(
+            Arg::new("set")
+                .long("set")
+                .value_name("KEY=VALUE")
+                .action(ArgAction::Append)
+                .global(true)
+                .help("Override a config value, e.g. --set qdrant.uri=http://localhost:6334")
+        )
         .subcommand(
             new_start_cmd().arg(
                 Arg::new("daemon")
@@ -43,7 +79,15 @@ lazy_static! {
             )
         )
         .subcommand(new_stop_cmd())
-        .subcommand(new_config_cmd());
+        .subcommand(new_status_cmd())
+        .subcommand(new_config_cmd())
+        .subcommand(new_embed_cmd())
+        .subcommand(new_ingest_cmd())
+        .subcommand(new_import_cmd())
+        .subcommand(new_export_cmd())
+        .subcommand(new_search_cmd())
+        .subcommand(new_eval_cmd())
+        .subcommand(new_bench_cmd());
 }
 
 pub fn run_app() {
@@ -52,14 +96,40 @@ pub fn run_app() {
 }
 
 fn cmd_match(matches: &ArgMatches) {
-    if let Some(c) = matches.get_one::<String>("config") {
-        set_config(c);
-    } else {
-        set_config("");
+    let sets = matches
+        .get_many::<String>("set")
+        .map(|v| v.cloned().collect::<Vec<String>>())
+        .unwrap_or_default();
+    let config_file = matches
+        .get_one::<String>("config")
+        .cloned()
+        .unwrap_or_default();
+    // config validate 不设置全局配置，目标文件有误时同样输出检查结果
+    if let Some(validate) = matches
+        .subcommand_matches("config")
+        .and_then(|c| c.subcommand_matches("validate"))
+    {
+        let file = validate.get_one::<String>("file").unwrap_or(&config_file);
+        validate_config_file(file, &sets);
+        return;
+    }
+    if let Err(e) = set_config(&config_file, &sets) {
+        eprintln!("{}", e);
+        exit(1);
+    }
+    if let Err(e) = tracing_init(&get_config().unwrap().log) {
+        eprintln!("{}", e);
+        exit(1);
     }
 
     if let Some(ref matches) = matches.subcommand_matches("start") {
+        let pid_file = get_config().unwrap().daemon.pid_file;
         if matches.get_flag("daemon") {
+            // 已有服务运行时不再 fork
+            if pid_file_locked(Path::new(&pid_file)) {
+                eprintln!("server already running, pid file {}", pid_file);
+                exit(1);
+            }
             let args: Vec<String> = env::args().collect();
             if let Ok(Fork::Child) = daemon(true, true) {
                 // Start child thread
@@ -74,8 +144,9 @@ fn cmd_match(matches: &ArgMatches) {
                     cmd.arg(arg);
                 }
 
+                // pid 文件由子进程启动时写入并加锁；当前进程随即退出，子进程交由 init 回收，不等待
                 let child = cmd.spawn().expect("Child process failed to start.");
-                fs::write("pid", child.id().to_string()).expect("Write pid file error!");
+                drop(child);
             }
             println!("{}", "daemon mod");
             std::process::exit(0);
@@ -102,32 +173,62 @@ fn cmd_match(matches: &ArgMatches) {
 
         println!("{}", banner);
         println!("current pid is:{}", std::process::id());
+        // 持有 pid 文件锁直到退出，拒绝同一 pid 文件的第二个服务
+        let pid_file = match PidFile::acquire(&pid_file) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1);
+            }
+        };
+        // 运行时长包含模型加载时间
+        let started_at = now_secs();
 
-        //启动公共 tokio runtime
-        GLOBAL_RUNTIME.block_on(async {
-            log::info!("global runtime start!");
-            // 启动全局资源
-            init_resources().await.unwrap();
-            // 加载model
-            GLOBAL_EMBEDDING_MODEL
-                .get_or_init(init_model_and_tokenizer)
-                .await;
-            GLOBAL_PIPELINE.get_or_init(init_global_pipeline).await;
-            // GLOBAL_INFERENCE_MODEL
-            //     .get_or_init(init_inference_model)
-            //     .await;
+        // 信号处理在 http server 启动前注册，加载期间收到终止信号同样开始 drain
+        let mut sigs = vec![];
+        sigs.extend(TERM_SIGNALS);
+        sigs.push(SIGHUP);
+        let mut signals = SignalsInfo::<WithOrigin>::new(&sigs).unwrap();
+        let thread_signale = thread::spawn(move || {
+            // 添加signal处理机制
+            for info in &mut signals {
+                // Will print info about signal + where it comes from.
+                log::info!("Received a signal {:?}", info);
+                // for kv in GLOBAL_TASK_STOP_MARK_MAP.iter() {
+                //     kv.store(true, std::sync::atomic::Ordering::SeqCst);
+                // }
+                // GLOBAL_TASK_STOP_MARK_MAP.store(true, std::sync::atomic::Ordering::SeqCst);
+                match info.signal {
+                    // SIGHUP 重新加载配置，结果由 reload_config 记录日志
+                    SIGHUP => {
+                        if let Err(e) = reload_config() {
+                            log::error!("reload config error: {}", e);
+                        }
+                    }
+                    // 第一次收到终止信号时开始 drain，再次收到时强制退出
+                    _ => {
+                        if !GLOBAL_SHUTDOWN.start() {
+                            eprintln!("Force exit");
+                            exit(1);
+                        }
+                        eprintln!("Terminating......");
+                        GLOBAL_TASK_MANAGER.stop_all();
+                    }
+                }
+            }
         });
 
+        // 先启动 http server，模型加载期间 /health/live 可用，/health/ready 及 /api 返回 503
         let async_http_server = async {
             let config = get_config().unwrap();
-            let bind = config.http.bind;
-            let port = config.http.port;
-
-            let mut http_server = httpserver::HttpServer::default().await;
-            let ip = IpAddr::from_str(&bind).unwrap();
-            let addr = net::SocketAddr::from((ip, port));
-
-            http_server.listener = TcpListener::bind(addr).await.unwrap();
+            let http_server = match httpserver::HttpServer::from_config(&config.http).await {
+                Ok(s) => s,
+                Err(e) => {
+                    log::error!("{}", e);
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            };
 
             let http_handler = http_server.run().await;
             let _http = tokio::join!(http_handler);
@@ -143,63 +244,461 @@ fn cmd_match(matches: &ArgMatches) {
             rt.block_on(async_http_server);
         });
 
-        let thread_signale = thread::spawn(|| {
-            // 添加signal处理机制
-            let mut sigs = vec![];
-            sigs.extend(TERM_SIGNALS);
-            let mut signals = SignalsInfo::<WithOrigin>::new(&sigs).unwrap();
-            for info in &mut signals {
-                // Will print info about signal + where it comes from.
-                log::info!("Received a signal {:?}", info);
-                // for kv in GLOBAL_TASK_STOP_MARK_MAP.iter() {
-                //     kv.store(true, std::sync::atomic::Ordering::SeqCst);
-                // }
-                // GLOBAL_TASK_STOP_MARK_MAP.store(true, std::sync::atomic::Ordering::SeqCst);
-                match info.signal {
-                    SIGTERM => {
-                        println!("kill !");
-                        exit(1);
+        //启动公共 tokio runtime
+        GLOBAL_RUNTIME.block_on(async {
+            log::info!("global runtime start!");
+            // 每一步加载均与终止信号竞争，加载期间收到终止信号时不再加载后续模型及启动后台任务
+            // 启动全局资源
+            tokio::select! {
+                r = init_resources() => r.unwrap(),
+                _ = GLOBAL_SHUTDOWN.wait() => return,
+            }
+            // 加载model
+            tokio::select! {
+                _ = GLOBAL_EMBEDDING_MODEL.get_or_init(init_model_and_tokenizer) => {}
+                _ = GLOBAL_SHUTDOWN.wait() => return,
+            }
+            tokio::select! {
+                _ = GLOBAL_PIPELINE.get_or_init(init_global_pipeline) => {}
+                _ = GLOBAL_SHUTDOWN.wait() => return,
+            }
+            // 按需加载 reranker
+            if get_config().unwrap().reranker.enable {
+                tokio::select! {
+                    _ = GLOBAL_RERANKER.get_or_init(init_global_reranker) => {}
+                    _ = GLOBAL_SHUTDOWN.wait() => return,
+                }
+            }
+            if GLOBAL_SHUTDOWN.is_draining() {
+                return;
+            }
+            // 目录同步
+            let watch = get_config().unwrap().watch;
+            if watch.enable {
+                GLOBAL_RUNTIME.spawn(async move {
+                    if let Err(e) = watch_folder(watch).await {
+                        log::error!("watch folder error: {}", e);
                     }
-                    term_sig => {
-                        eprintln!("Terminating......");
-                        // do some before exit
-                        exit(0)
+                });
+            }
+            // 恢复持久化的任务
+            if let Err(e) = GLOBAL_TASK_MANAGER.load() {
+                log::error!("load tasks error: {}", e);
+            }
+            // 监听配置文件变化
+            let reload = get_config().unwrap().reload;
+            let config_file = GLOBAL_CONFIG_SOURCES.read().unwrap().file.clone();
+            if let (true, Some(file)) = (reload.watch_file, config_file) {
+                thread::spawn(move || {
+                    if let Err(e) = watch_config_file(&file, reload.debounce_ms) {
+                        log::error!("watch config file error: {}", e);
                     }
-                }
+                });
             }
+            // GLOBAL_INFERENCE_MODEL
+            //     .get_or_init(init_inference_model)
+            //     .await;
+            GLOBAL_STARTUP.finish();
+            log::info!("startup finished");
         });
+        if let Err(e) = server_state(started_at).save(pid_file.path()) {
+            log::error!("save server state error: {}", e);
+        }
+
+        // http server 在 drain 完成或超时后退出
         thread_http.join().unwrap();
-        thread_signale.join().unwrap();
+        let drain_timeout = Duration::from_secs(get_config().unwrap().http.drain_timeout_secs);
+        if let Err(e) = GLOBAL_TASK_MANAGER.checkpoint(GLOBAL_SHUTDOWN.remaining(drain_timeout)) {
+            log::error!("checkpoint tasks error: {}", e);
+        }
+        log::info!("server stopped");
+        drop(thread_signale);
+        drop(pid_file);
     }
 
     if let Some(ref _matches) = matches.subcommand_matches("stop") {
-        println!("server stopping...");
-        let sys =
-            System::new_with_specifics(RefreshKind::everything().without_cpu().without_memory());
-        let pidstr = String::from_utf8(fs::read("pid").unwrap()).unwrap();
-        let pid = Pid::from_str(pidstr.as_str()).unwrap();
-
-        if let Some(p) = sys.process(pid) {
-            println!("terminal process: {:?}", p.pid());
-        } else {
-            println!("Server not run!");
-            return;
+        stop_server();
+    }
+
+    if let Some(_matches) = matches.subcommand_matches("status") {
+        print_server_status();
+    }
+
+    if let Some(embed) = matches.subcommand_matches("embed") {
+        let embed_file = EmbedFile {
+            input: embed.get_one::<String>("input").cloned(),
+            jsonl: embed.get_flag("jsonl"),
+            text_field: embed.get_one::<String>("text_field").cloned(),
+            format: EmbedFormat::from_str(embed.get_one::<String>("format").unwrap()).unwrap(),
+            output: embed.get_one::<String>("output").cloned(),
+            batch_size: *embed.get_one::<usize>("batch_size").unwrap(),
         };
-        Command::new("kill")
-            .args(["-15", pidstr.as_str()])
-            .output()
-            .expect("failed to execute process");
+
+        GLOBAL_RUNTIME.block_on(async {
+            GLOBAL_EMBEDDING_MODEL
+                .get_or_init(init_model_and_tokenizer)
+                .await;
+            // 向量可能写到标准输出，统计信息写到标准错误
+            match embed_file.execute().await {
+                Ok((rows, dimension)) => {
+                    eprintln!("{} lines embedded, dimension {}", rows, dimension)
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            }
+        });
     }
 
-    if let Some(config) = matches.subcommand_matches("config") {
-        if let Some(_show) = config.subcommand_matches("show") {
-            let yml = get_current_config_yml();
-            match yml {
-                Ok(str) => {
-                    println!("{}", str);
+    if let Some(ingest) = matches.subcommand_matches("ingest") {
+        let folder = ingest.get_one::<String>("folder").unwrap().clone();
+        let include_regex = ingest
+            .get_many::<String>("include")
+            .map(|v| v.cloned().collect::<Vec<String>>());
+        let exclude_regex = ingest
+            .get_many::<String>("exclude")
+            .map(|v| v.cloned().collect::<Vec<String>>());
+        let last_modify_filter = match (
+            ingest.get_one::<usize>("modified_after"),
+            ingest.get_one::<usize>("modified_before"),
+        ) {
+            (Some(ts), _) => Some(LastModifyFilter {
+                filter_type: LastModifyFilterType::Greater,
+                timestamp: *ts,
+            }),
+            (None, Some(ts)) => Some(LastModifyFilter {
+                filter_type: LastModifyFilterType::Less,
+                timestamp: *ts,
+            }),
+            (None, None) => None,
+        };
+        let ingest_folder = IngestFolder {
+            folder,
+            include_regex,
+            exclude_regex,
+            last_modify_filter,
+        };
+
+        GLOBAL_RUNTIME.block_on(async {
+            if let Err(e) = init_resources().await {
+                eprintln!("{}", e);
+                exit(1);
+            }
+            GLOBAL_EMBEDDING_MODEL
+                .get_or_init(init_model_and_tokenizer)
+                .await;
+            match ingest_folder.execute().await {
+                Ok(report) => match struct_to_json_string(&report) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        exit(1);
+                    }
+                },
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            }
+        });
+    }
+
+    if let Some(search) = matches.subcommand_matches("search") {
+        let query = search.get_one::<String>("query").unwrap().clone();
+        let limit = *search.get_one::<u64>("limit").unwrap();
+        let mut filter = HashMap::new();
+        for kv in search.get_many::<String>("filter").into_iter().flatten() {
+            match kv.split_once('=') {
+                Some((k, v)) => {
+                    filter.insert(k.to_string(), parse_filter_value(v));
+                }
+                None => {
+                    eprintln!("filter must be KEY=VALUE: {}", kv);
+                    exit(1);
+                }
+            }
+        }
+        let options = RetrieverOptions {
+            rerank: search.get_one::<bool>("rerank").copied(),
+            diversify: None,
+            filter: Some(filter),
+            score_threshold: search.get_one::<f32>("threshold").copied(),
+        };
+        let fields = match search.get_many::<String>("fields") {
+            Some(f) => f.cloned().collect::<Vec<String>>(),
+            None => vec![get_config().unwrap().ingest.text_field],
+        };
+
+        GLOBAL_RUNTIME.block_on(async {
+            if let Err(e) = init_resources().await {
+                eprintln!("{}", e);
+                exit(1);
+            }
+            GLOBAL_EMBEDDING_MODEL
+                .get_or_init(init_model_and_tokenizer)
+                .await;
+            if options
+                .rerank
+                .unwrap_or(get_config().unwrap().reranker.enable)
+            {
+                GLOBAL_RERANKER.get_or_init(init_global_reranker).await;
+            }
+            match retriever(&query, limit, &options).await {
+                Ok(points) => print_search_result(&points, &fields, search.get_flag("json")),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
                 }
+            }
+        });
+    }
+
+    if let Some(eval) = matches.subcommand_matches("eval") {
+        let diversify = match eval.get_one::<String>("diversify") {
+            Some(d) => match serde_json::from_str(d) {
+                Ok(d) => Some(d),
+                Err(e) => {
+                    eprintln!("invalid diversify: {}", e);
+                    exit(1);
+                }
+            },
+            None => None,
+        };
+        let eval_retriever = EvalRetriever {
+            file: eval.get_one::<String>("file").unwrap().clone(),
+            k: *eval.get_one::<u64>("k").unwrap(),
+            id_field: eval.get_one::<String>("id_field").cloned(),
+            options: RetrieverOptions {
+                rerank: eval.get_one::<bool>("rerank").copied(),
+                diversify,
+                filter: None,
+                score_threshold: None,
+            },
+        };
+        let output = eval.get_one::<String>("output").cloned();
+
+        GLOBAL_RUNTIME.block_on(async {
+            if let Err(e) = init_resources().await {
+                eprintln!("{}", e);
+                exit(1);
+            }
+            GLOBAL_EMBEDDING_MODEL
+                .get_or_init(init_model_and_tokenizer)
+                .await;
+            let rerank = eval_retriever
+                .options
+                .rerank
+                .unwrap_or(get_config().unwrap().reranker.enable);
+            if rerank {
+                GLOBAL_RERANKER.get_or_init(init_global_reranker).await;
+            }
+            let report = match eval_retriever.execute().await {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            };
+            let headers = vec![
+                "queries".to_string(),
+                format!("recall@{}", report.k),
+                "mrr".to_string(),
+                format!("ndcg@{}", report.k),
+                "p50 ms".to_string(),
+                "p95 ms".to_string(),
+                "p99 ms".to_string(),
+            ];
+            let row = vec![
+                report.queries.to_string(),
+                format!("{:.4}", report.recall_at_k),
+                format!("{:.4}", report.mrr),
+                format!("{:.4}", report.ndcg_at_k),
+                format!("{:.1}", report.latency_ms.p50),
+                format!("{:.1}", report.latency_ms.p95),
+                format!("{:.1}", report.latency_ms.p99),
+            ];
+            println!("{}", format_table(&headers, &[row]));
+            if let Some(f) = output {
+                let r = serde_json::to_string_pretty(&report)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|json| fs::write(&f, json).map_err(anyhow::Error::from));
+                match r {
+                    Ok(_) => println!("report written to {}", f),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        exit(1);
+                    }
+                }
+            }
+        });
+    }
+
+    if let Some(bench) = matches.subcommand_matches("bench") {
+        if let Some(device) = bench.get_one::<String>("device") {
+            GLOBAL_CONFIG.write().unwrap().model.device = device.clone();
+        }
+        let bench_embedding = BenchEmbedding {
+            lengths: bench
+                .get_many::<usize>("lengths")
+                .unwrap()
+                .copied()
+                .collect(),
+            batch_sizes: bench
+                .get_many::<usize>("batch_sizes")
+                .unwrap()
+                .copied()
+                .collect(),
+            concurrency: bench
+                .get_many::<usize>("concurrency")
+                .unwrap()
+                .copied()
+                .collect(),
+            iterations: *bench.get_one::<usize>("iterations").unwrap(),
+        };
+        let output = bench.get_one::<String>("output").cloned();
+
+        GLOBAL_RUNTIME.block_on(async {
+            let model_id = get_config().unwrap().model.model_id;
+            GLOBAL_EMBEDDING_MODEL
+                .get_or_init(init_model_and_tokenizer)
+                .await;
+            let report = match bench_embedding.execute(&model_id).await {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            };
+            println!(
+                "model: {}, device: {}, dtype: {}",
+                report.model_id, report.device, report.dtype
+            );
+            let headers = [
+                "words",
+                "tokens",
+                "batch",
+                "concurrency",
+                "emb/s",
+                "tokens/s",
+                "p50 ms",
+                "p95 ms",
+                "p99 ms",
+            ]
+            .iter()
+            .map(|h| h.to_string())
+            .collect::<Vec<String>>();
+            let rows = report
+                .results
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.length.to_string(),
+                        r.tokens_per_input.to_string(),
+                        r.batch_size.to_string(),
+                        r.concurrency.to_string(),
+                        format!("{:.1}", r.embeddings_per_sec),
+                        format!("{:.0}", r.tokens_per_sec),
+                        format!("{:.1}", r.latency_ms.p50),
+                        format!("{:.1}", r.latency_ms.p95),
+                        format!("{:.1}", r.latency_ms.p99),
+                    ]
+                })
+                .collect::<Vec<Vec<String>>>();
+            println!("{}", format_table(&headers, &rows));
+            if let Some(f) = output {
+                let r = serde_json::to_string_pretty(&report)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|json| fs::write(&f, json).map_err(anyhow::Error::from));
+                match r {
+                    Ok(_) => println!("report written to {}", f),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        exit(1);
+                    }
+                }
+            }
+        });
+    }
+
+    if let Some(import) = matches.subcommand_matches("import") {
+        let import_jsonl = ImportJsonl {
+            file: import.get_one::<String>("file").unwrap().clone(),
+            text_field: import.get_one::<String>("text_field").cloned(),
+            id_field: import.get_one::<String>("id_field").cloned(),
+            collection: import.get_one::<String>("collection").cloned(),
+            batch_size: import.get_one::<usize>("batch_size").copied(),
+        };
+
+        GLOBAL_RUNTIME.block_on(async {
+            if let Err(e) = init_resources().await {
+                eprintln!("{}", e);
+                exit(1);
+            }
+            GLOBAL_EMBEDDING_MODEL
+                .get_or_init(init_model_and_tokenizer)
+                .await;
+            match import_jsonl
+                .execute_with_progress(&TaskProgress::default())
+                .await
+            {
+                Ok(report) => match struct_to_json_string(&report) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        exit(1);
+                    }
+                },
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            }
+        });
+    }
+
+    if let Some(export) = matches.subcommand_matches("export") {
+        let export_jsonl = ExportJsonl {
+            file: export.get_one::<String>("file").cloned(),
+            collection: export.get_one::<String>("collection").cloned(),
+            with_vectors: export.get_flag("with_vectors"),
+            batch_size: *export.get_one::<u32>("batch_size").unwrap(),
+        };
+
+        GLOBAL_RUNTIME.block_on(async {
+            if let Err(e) = init_resources().await {
+                eprintln!("{}", e);
+                exit(1);
+            }
+            // 输出可能是标准输出，报告写到标准错误
+            match export_jsonl
+                .execute_with_progress(&TaskProgress::default())
+                .await
+            {
+                Ok(report) => eprintln!("{} points exported", report.points),
                 Err(e) => {
                     eprintln!("{}", e);
+                    exit(1);
+                }
+            }
+        });
+    }
+
+    if let Some(config) = matches.subcommand_matches("config") {
+        if let Some(show) = config.subcommand_matches("show") {
+            match show.subcommand_name() {
+                Some("info") => print_runtime_info(),
+                Some("all") => print_config_sources(),
+                _ => {
+                    let yml = get_current_config_yml();
+                    match yml {
+                        Ok(str) => {
+                            println!("{}", str);
+                        }
+                        Err(e) => {
+                            eprintln!("{}", e);
+                        }
+                    }
                 }
             }
         }
@@ -219,3 +718,308 @@ fn cmd_match(matches: &ArgMatches) {
         }
     }
 }
+
+fn validate_config_file(file: &str, sets: &[String]) {
+    let c = match load_config(file, sets) {
+        Ok((c, _)) => c,
+        Err(e) => {
+            println!("[fail] parse: {}", e);
+            exit(1);
+        }
+    };
+    let results = GLOBAL_RUNTIME.block_on(validate_config(&c));
+    for r in &results {
+        let flag = match r.ok {
+            true => "ok",
+            false => "fail",
+        };
+        println!("[{}] {}: {}", flag, r.name, r.message);
+    }
+    let failed = results.iter().filter(|r| !r.ok).count();
+    if failed > 0 {
+        println!("{} problems found", failed);
+        exit(1);
+    }
+    println!("config is valid");
+}
+
+fn print_runtime_info() {
+    let config = match get_config() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let config_file = GLOBAL_CONFIG_SOURCES
+        .read()
+        .ok()
+        .and_then(|s| s.file.clone())
+        .unwrap_or("-".to_string());
+    let listeners = match config.http.listen_addrs() {
+        Ok(addrs) => addrs
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>()
+            .join(", "),
+        Err(e) => e.to_string(),
+    };
+    let mut sys = System::new();
+    sys.refresh_memory();
+    let rows = vec![
+        ("version", env!("CARGO_PKG_VERSION").to_string()),
+        ("config file", config_file),
+        ("listeners", listeners),
+        ("model", config.model.model_id.clone()),
+        ("device", config.model.device.clone()),
+        ("cuda feature", cfg!(feature = "cuda").to_string()),
+        (
+            "cuda available",
+            candle_core::utils::cuda_is_available().to_string(),
+        ),
+        ("qdrant", config.qdrant.uri.clone()),
+        ("collection", config.qdrant.collection.clone()),
+        (
+            "os",
+            format!(
+                "{} {}",
+                System::name().unwrap_or_default(),
+                System::os_version().unwrap_or_default()
+            ),
+        ),
+        ("cpus", num_cpus::get().to_string()),
+        (
+            "memory",
+            format!(
+                "{} MiB / {} MiB",
+                sys.used_memory() / 1024 / 1024,
+                sys.total_memory() / 1024 / 1024
+            ),
+        ),
+    ]
+    .into_iter()
+    .map(|(k, v)| vec![k.to_string(), v])
+    .collect::<Vec<Vec<String>>>();
+    let headers = ["item", "value"].map(|h| h.to_string());
+    println!("{}", format_table(&headers, &rows));
+}
+
+// 脱敏后的全部配置项及来源
+fn print_config_sources() {
+    let sources = match GLOBAL_CONFIG_SOURCES.read() {
+        Ok(s) => s.clone(),
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let inspect = match get_config().and_then(|c| inspect_config(&c, &sources)) {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    println!("config file: {}", inspect.file.unwrap_or("-".to_string()));
+    let rows = inspect
+        .entries
+        .iter()
+        .map(|e| {
+            vec![
+                e.key.clone(),
+                table_cell(&e.value.to_string(), 60),
+                format!("{:?}", e.source).to_lowercase(),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+    let headers = ["key", "value", "source"].map(|h| h.to_string());
+    println!("{}", format_table(&headers, &rows));
+}
+
+// 数字、布尔值按类型匹配，其余按字符串匹配
+fn parse_filter_value(value: &str) -> serde_json::Value {
+    if let Ok(i) = value.parse::<i64>() {
+        return serde_json::Value::from(i);
+    }
+    match value {
+        "true" => serde_json::Value::Bool(true),
+        "false" => serde_json::Value::Bool(false),
+        _ => serde_json::Value::String(value.to_string()),
+    }
+}
+
+fn print_search_result(points: &[RetrievedPoint], fields: &[String], json: bool) {
+    let results = points
+        .iter()
+        .enumerate()
+        .map(|(idx, p)| {
+            let payload = serde_json::Value::from(Payload::from(p.point.payload.clone()));
+            let selected = fields
+                .iter()
+                .map(|f| (f.clone(), payload.get(f).cloned().unwrap_or_default()))
+                .collect::<serde_json::Map<String, serde_json::Value>>();
+            serde_json::json!({
+                "rank": idx + 1,
+                "id": point_id_to_string(&p.point.id),
+                "score": p.point.score,
+                "rerank_score": p.rerank_score,
+                "payload": selected,
+            })
+        })
+        .collect::<Vec<serde_json::Value>>();
+
+    if json {
+        match serde_json::to_string_pretty(&results) {
+            Ok(s) => println!("{}", s),
+            Err(e) => eprintln!("{}", e),
+        }
+        return;
+    }
+
+    let mut headers = vec!["rank", "id", "score", "rerank_score"]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<String>>();
+    headers.extend(fields.iter().cloned());
+    let rows = results
+        .iter()
+        .map(|r| {
+            let mut row = vec![
+                r["rank"].to_string(),
+                r["id"].as_str().unwrap_or_default().to_string(),
+                format!("{:.4}", r["score"].as_f64().unwrap_or_default()),
+                r["rerank_score"]
+                    .as_f64()
+                    .map_or(String::new(), |s| format!("{:.4}", s)),
+            ];
+            for f in fields {
+                let cell = match &r["payload"][f] {
+                    serde_json::Value::String(s) => s.clone(),
+                    serde_json::Value::Null => String::new(),
+                    v => v.to_string(),
+                };
+                row.push(table_cell(&cell, 60));
+            }
+            row
+        })
+        .collect::<Vec<Vec<String>>>();
+    println!("{}", format_table(&headers, &rows));
+}
+
+// 启动完成后的服务状态，供 status 命令读取
+fn server_state(started_at: u64) -> ServerState {
+    let config = get_config().unwrap();
+    ServerState {
+        pid: std::process::id(),
+        started_at,
+        config_file: GLOBAL_CONFIG_SOURCES
+            .read()
+            .ok()
+            .and_then(|s| s.file.clone()),
+        model: config.model.model_id.clone(),
+        reranker: config
+            .reranker
+            .enable
+            .then(|| config.reranker.model_id.clone()),
+        generation_model: GENERATION_MODEL_ID.to_string(),
+        listeners: config
+            .http
+            .listen_addrs()
+            .map(|addrs| addrs.iter().map(|a| a.to_string()).collect())
+            .unwrap_or_default(),
+    }
+}
+
+// 发送 SIGTERM 并等待服务退出，pid 文件未被锁定或进程不存在时视为遗留文件并删除
+fn stop_server() {
+    let daemon = get_config().unwrap().daemon;
+    let path = Path::new(&daemon.pid_file);
+    let pid = match read_pid(path) {
+        Ok(Some(pid)) => pid,
+        Ok(None) => {
+            println!("Server not run!");
+            return;
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+    if !pid_file_locked(path) || !process_alive(pid) {
+        let _ = fs::remove_file(state_file_path(path));
+        match fs::remove_file(path) {
+            Ok(_) => println!("Server not run! stale pid file {} removed", path.display()),
+            Err(e) => eprintln!("remove stale pid file {} error: {}", path.display(), e),
+        }
+        return;
+    }
+
+    println!("server stopping, pid {}", pid);
+    if let Err(e) = send_signal(pid, libc::SIGTERM) {
+        eprintln!("{}", e);
+        exit(1);
+    }
+    let deadline = Instant::now() + Duration::from_secs(daemon.stop_timeout_secs);
+    while process_alive(pid) {
+        if Instant::now() >= deadline {
+            eprintln!(
+                "server {} still running after {}s",
+                pid, daemon.stop_timeout_secs
+            );
+            exit(1);
+        }
+        thread::sleep(Duration::from_millis(200));
+    }
+    println!("server stopped");
+}
+
+// 未运行时以退出码 3 退出，与 LSB init 脚本 status 约定一致
+fn print_server_status() {
+    let path = PathBuf::from(get_config().unwrap().daemon.pid_file);
+    let pid = match read_pid(&path) {
+        Ok(Some(pid)) if pid_file_locked(&path) && process_alive(pid) => pid,
+        Ok(Some(pid)) => {
+            println!(
+                "not running, stale pid file {} (pid {})",
+                path.display(),
+                pid
+            );
+            exit(3);
+        }
+        Ok(None) => {
+            println!("not running");
+            exit(3);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+
+    let mut rows = vec![
+        ("status", "running".to_string()),
+        ("pid", pid.to_string()),
+        ("pid file", path.display().to_string()),
+    ];
+    match ServerState::load(&path) {
+        Ok(state) => rows.extend([
+            (
+                "uptime",
+                format_uptime(now_secs().saturating_sub(state.started_at)),
+            ),
+            ("config file", state.config_file.unwrap_or("-".to_string())),
+            ("model", state.model),
+            ("reranker", state.reranker.unwrap_or("-".to_string())),
+            ("generation model", state.generation_model),
+            ("listeners", state.listeners.join(", ")),
+        ]),
+        // 模型加载完成前尚未写入状态文件
+        Err(_) => rows.push(("state", "starting".to_string())),
+    }
+    let rows = rows
+        .into_iter()
+        .map(|(k, v)| vec![k.to_string(), v])
+        .collect::<Vec<Vec<String>>>();
+    let headers = ["item", "value"].map(|h| h.to_string());
+    println!("{}", format_table(&headers, &rows));
+}
@@ -0,0 +1,42 @@
+use clap::{Arg, ArgAction, Command};
+
+pub fn new_search_cmd() -> Command {
+    clap::Command::new("search")
+        .about("run a query through the retriever and print ranked results")
+        .args(&[
+            Arg::new("query")
+                .value_name("query")
+                .required(true)
+                .index(1),
+            Arg::new("limit")
+                .short('l')
+                .long("limit")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("10"),
+            Arg::new("filter")
+                .long("filter")
+                .value_name("KEY=VALUE")
+                .action(ArgAction::Append)
+                .help("payload exact match, repeatable, e.g. --filter path=docs/a.md"),
+            Arg::new("threshold")
+                .long("threshold")
+                .value_name("SCORE")
+                .value_parser(clap::value_parser!(f32))
+                .help("drop candidates whose vector score is below the threshold"),
+            Arg::new("rerank")
+                .long("rerank")
+                .value_name("BOOL")
+                .value_parser(clap::value_parser!(bool))
+                .help("override reranker.enable"),
+            Arg::new("fields")
+                .long("fields")
+                .value_name("FIELDS")
+                .value_delimiter(',')
+                .help("payload fields to print, default ingest.text_field"),
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("print json instead of a table"),
+        ])
+}
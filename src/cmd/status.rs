@@ -0,0 +1,5 @@
+use clap::Command;
+
+pub fn new_status_cmd() -> Command {
+    clap::Command::new("status").about("show server running state, uptime, models and listeners")
+}
@@ -1,10 +1,10 @@
 use super::{rand_util::rand_string, size_distributed, LastModifyFilter, RegexFilter};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::{
     collections::BTreeMap,
     fs::{self, File, OpenOptions},
     io::{self, BufRead, LineWriter, Read, Write},
-    path::Path,
+    path::{Component, Path, PathBuf},
     time::UNIX_EPOCH,
 };
 use walkdir::WalkDir;
@@ -140,6 +140,57 @@ pub fn analyze_folder_files_size(
     Ok(size_map)
 }
 
+// 遍历目录，返回满足过滤条件的文件路径及最后修改时间（秒）
+pub fn scan_folder_files(
+    folder: &str,
+    regex_filter: Option<RegexFilter>,
+    last_modify_filter: Option<LastModifyFilter>,
+) -> Result<Vec<(String, u64)>> {
+    // 目录不存在时返回错误，避免同步时当作空目录处理
+    if !Path::new(folder).is_dir() {
+        return Err(anyhow!("{} is not a directory", folder));
+    }
+    let mut files = vec![];
+    for entry in WalkDir::new(folder).into_iter() {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                tracing::warn!("skip entry in {}: {}", folder, e);
+                continue;
+            }
+        };
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        if let Some(p) = entry.path().to_str() {
+            if p.eq(folder) {
+                continue;
+            }
+
+            if let Some(f) = &regex_filter {
+                if !f.is_match(p) {
+                    continue;
+                }
+            }
+
+            let modified_time = entry
+                .metadata()?
+                .modified()?
+                .duration_since(UNIX_EPOCH)?
+                .as_secs();
+
+            if let Some(f) = &last_modify_filter {
+                if !f.is_match(TryFrom::try_from(modified_time)?) {
+                    continue;
+                }
+            }
+
+            files.push((p.to_string(), modified_time));
+        };
+    }
+    Ok(files)
+}
+
 // Todo
 // 加入正则过滤功能
 
@@ -397,9 +448,46 @@ pub fn gen_file_part_plan(file_path: &str, chunk_size: usize) -> Result<Vec<File
     Ok(vec_file_parts)
 }
 
+/// 将 path 解析为 root 下的绝对路径，相对路径相对于 root。
+/// 不允许包含 ..，解析符号链接后仍须位于 root 下；path 不存在时按其最近的已存在上级目录判断
+pub fn resolve_path_under(root: &str, path: &str) -> Result<PathBuf> {
+    let canonical_root = Path::new(root)
+        .canonicalize()
+        .map_err(|e| anyhow!("file root {} error: {}", root, e))?;
+    let p = Path::new(path);
+    if p.components().any(|c| c == Component::ParentDir) {
+        return Err(anyhow!("path {} must not contain ..", path));
+    }
+    let joined = match p.is_absolute() {
+        true => p.to_path_buf(),
+        false => canonical_root.join(p),
+    };
+
+    let mut existing = joined.as_path();
+    let mut missing = vec![];
+    while !existing.exists() {
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return Err(anyhow!("invalid path {}", path)),
+        }
+    }
+    let mut resolved = existing.canonicalize()?;
+    resolved.extend(missing.iter().rev());
+    if !resolved.starts_with(&canonical_root) {
+        return Err(anyhow!("path {} is outside file root {}", path, root));
+    }
+    Ok(resolved)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::commons::{fileutiles::generate_file, fill_file_with_zero, multi_parts_copy_file};
+    use crate::commons::{
+        fileutiles::generate_file, fill_file_with_zero, multi_parts_copy_file, resolve_path_under,
+        scan_folder_files,
+    };
 
     //cargo test commons::fileutiles::test::test_gen_file -- --nocapture
     #[test]
@@ -421,4 +509,40 @@ mod test {
         let r = fill_file_with_zero(1024 * 1024 * 1024 * 10, 1024 * 1024, "/tmp/zero_file");
         println!("test_fill_file_with_zero {:?}", r);
     }
+
+    //cargo test commons::fileutiles::test::test_resolve_path_under -- --nocapture
+    #[test]
+    fn test_resolve_path_under() {
+        let root = std::env::temp_dir().join(format!("file_root_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        let root_str = root.display().to_string();
+        let canonical_root = root.canonicalize().unwrap();
+
+        let r = resolve_path_under(&root_str, "docs/a.jsonl").unwrap();
+        assert_eq!(r, canonical_root.join("docs/a.jsonl"));
+        let absolute = canonical_root.join("out/b.jsonl").display().to_string();
+        assert!(resolve_path_under(&root_str, &absolute).is_ok());
+        assert!(resolve_path_under(&root_str, "docs/../../etc/passwd").is_err());
+        assert!(resolve_path_under(&root_str, "/etc/passwd").is_err());
+        // 指向 root 之外的符号链接
+        std::os::unix::fs::symlink("/etc", root.join("etc")).unwrap();
+        assert!(resolve_path_under(&root_str, "etc/passwd").is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    //cargo test commons::fileutiles::test::test_scan_folder_files -- --nocapture
+    #[test]
+    fn test_scan_folder_files() {
+        let root = std::env::temp_dir().join(format!("scan_folder_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/a.md"), "a").unwrap();
+        let root_str = root.display().to_string();
+        let files = scan_folder_files(&root_str, None, None).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].0.ends_with("sub/a.md"));
+        // 目录不存在或不是目录时返回错误
+        assert!(scan_folder_files(&format!("{}/missing", root_str), None, None).is_err());
+        assert!(scan_folder_files(&format!("{}/sub/a.md", root_str), None, None).is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
 }
@@ -6,10 +6,12 @@ mod notify_utile;
 
 mod rand_util;
 mod sysutiles;
+mod table_utile;
 mod yamlutile;
 pub use convert::*;
 pub use fileutiles::*;
 pub use filters::*;
 pub use json_utile::*;
 pub use notify_utile::*;
+pub use table_utile::*;
 pub use yamlutile::*;
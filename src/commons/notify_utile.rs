@@ -1,6 +1,7 @@
 use super::struct_to_json_string;
 use notify::{
-    event::CreateKind, Config, Error, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    event::{CreateKind, RenameMode},
+    Config, Error, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -13,7 +14,7 @@ use std::{
         Arc,
     },
 };
-use tokio::task::yield_now;
+use tokio::{sync::mpsc::UnboundedSender, task::yield_now};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -81,50 +82,7 @@ impl NotifyWatcher {
             if !self.writing_file_status {
                 return;
             }
-            let mut modified = Modified::new();
-            match res {
-                Ok(event) => {
-                    modified.path = event.paths[0].as_path().display().to_string();
-                    match event.kind {
-                        EventKind::Create(c) => match c {
-                            CreateKind::File => {
-                                modified.modify_type = ModifyType::Create;
-                                modified.path_type = PathType::File;
-                            }
-                            CreateKind::Folder => {
-                                modified.modify_type = ModifyType::Create;
-                                modified.path_type = PathType::Folder;
-                            }
-                            CreateKind::Any => {}
-                            CreateKind::Other => {}
-                        },
-                        EventKind::Modify(m) => match m {
-                            notify::event::ModifyKind::Data(_) => {
-                                modified.modify_type = ModifyType::Modify;
-                                modified.path_type = PathType::File;
-                            }
-                            notify::event::ModifyKind::Any => {}
-                            notify::event::ModifyKind::Metadata(_) => {}
-                            notify::event::ModifyKind::Name(_) => {}
-                            notify::event::ModifyKind::Other => {}
-                        },
-                        EventKind::Remove(r) => match r {
-                            notify::event::RemoveKind::File => {
-                                modified.modify_type = ModifyType::Delete;
-                                modified.path_type = PathType::File;
-                            }
-                            notify::event::RemoveKind::Folder => {
-                                modified.modify_type = ModifyType::Delete;
-                                modified.path_type = PathType::Folder;
-                            }
-                            notify::event::RemoveKind::Any => {}
-                            notify::event::RemoveKind::Other => {}
-                        },
-                        _ => {}
-                    }
-                }
-                Err(error) => println!("{}", error),
-            }
+            let modified = event_to_modified(res);
 
             match modified.modify_type {
                 ModifyType::Unkown => {}
@@ -151,6 +109,19 @@ impl NotifyWatcher {
         }
     }
 
+    // 将事件发送至 sender，供目录同步消费，接收端关闭后退出
+    pub fn watch_to_sender(self, sender: UnboundedSender<Modified>) {
+        for res in self.reciver {
+            let modified = event_to_modified(res);
+            if let ModifyType::Unkown = modified.modify_type {
+                continue;
+            }
+            if sender.send(modified).is_err() {
+                return;
+            }
+        }
+    }
+
     #[allow(dead_code)]
     pub fn stop_write_file(&mut self) {
         self.writing_file_status = false;
@@ -162,6 +133,68 @@ impl NotifyWatcher {
     }
 }
 
+// 将 notify 事件转换为 Modified，无需关注的事件 modify_type 为 Unkown
+fn event_to_modified(res: Result<Event, Error>) -> Modified {
+    let mut modified = Modified::new();
+    match res {
+        Ok(event) => {
+            // Rescan 等事件不带路径，按 Unkown 返回，由调用方跳过
+            modified.path = match event.paths.first() {
+                Some(p) => p.as_path().display().to_string(),
+                None => return modified,
+            };
+            match event.kind {
+                EventKind::Create(c) => match c {
+                    CreateKind::File => {
+                        modified.modify_type = ModifyType::Create;
+                        modified.path_type = PathType::File;
+                    }
+                    CreateKind::Folder => {
+                        modified.modify_type = ModifyType::Create;
+                        modified.path_type = PathType::Folder;
+                    }
+                    CreateKind::Any => {}
+                    CreateKind::Other => {}
+                },
+                EventKind::Modify(m) => match m {
+                    notify::event::ModifyKind::Data(_) => {
+                        modified.modify_type = ModifyType::Modify;
+                        modified.path_type = PathType::File;
+                    }
+                    notify::event::ModifyKind::Any => {}
+                    notify::event::ModifyKind::Metadata(_) => {}
+                    // 编辑器常以临时文件重命名的方式保存
+                    notify::event::ModifyKind::Name(RenameMode::From) => {
+                        modified.modify_type = ModifyType::Delete;
+                        modified.path_type = PathType::File;
+                    }
+                    notify::event::ModifyKind::Name(RenameMode::To) => {
+                        modified.modify_type = ModifyType::Create;
+                        modified.path_type = PathType::File;
+                    }
+                    notify::event::ModifyKind::Name(_) => {}
+                    notify::event::ModifyKind::Other => {}
+                },
+                EventKind::Remove(r) => match r {
+                    notify::event::RemoveKind::File => {
+                        modified.modify_type = ModifyType::Delete;
+                        modified.path_type = PathType::File;
+                    }
+                    notify::event::RemoveKind::Folder => {
+                        modified.modify_type = ModifyType::Delete;
+                        modified.path_type = PathType::Folder;
+                    }
+                    notify::event::RemoveKind::Any => {}
+                    notify::event::RemoveKind::Other => {}
+                },
+                _ => {}
+            }
+        }
+        Err(error) => println!("{}", error),
+    }
+    modified
+}
+
 #[cfg(test)]
 mod test {
     use std::{
@@ -171,7 +204,8 @@ mod test {
 
     use tokio::{runtime, task::JoinSet};
 
-    use super::NotifyWatcher;
+    use super::{event_to_modified, ModifyType, NotifyWatcher};
+    use notify::{event::CreateKind, Event, EventKind};
 
     //cargo test commons::notify_utile::test::test_watcher -- --nocapture
     #[test]
@@ -203,4 +237,16 @@ mod test {
             }
         });
     }
+
+    //cargo test commons::notify_utile::test::test_event_without_path -- --nocapture
+    #[test]
+    fn test_event_without_path() {
+        let event = Event::new(EventKind::Create(CreateKind::File));
+        let modified = event_to_modified(Ok(event));
+        assert!(matches!(modified.modify_type, ModifyType::Unkown));
+        let event = Event::new(EventKind::Create(CreateKind::File)).add_path("/tmp/a.md".into());
+        let modified = event_to_modified(Ok(event));
+        assert!(matches!(modified.modify_type, ModifyType::Create));
+        assert_eq!(modified.path, "/tmp/a.md");
+    }
 }
@@ -0,0 +1,67 @@
+/// 将表头及各行格式化为对齐的纯文本表格，按字符数计算列宽
+pub fn format_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths = headers
+        .iter()
+        .map(|h| h.chars().count())
+        .collect::<Vec<usize>>();
+    for row in rows {
+        for (idx, cell) in row.iter().enumerate().take(widths.len()) {
+            widths[idx] = widths[idx].max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[String]| -> String {
+        let line = widths
+            .iter()
+            .enumerate()
+            .map(|(idx, width)| {
+                let cell = cells.get(idx).map_or("", |c| c.as_str());
+                let pad = width - cell.chars().count().min(*width);
+                format!("{}{}", cell, " ".repeat(pad))
+            })
+            .collect::<Vec<String>>()
+            .join(" | ");
+        line.trim_end().to_string()
+    };
+
+    let mut table = vec![format_row(headers)];
+    table.push(
+        widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<String>>()
+            .join("-+-"),
+    );
+    for row in rows {
+        table.push(format_row(row));
+    }
+    table.join("\n")
+}
+
+/// 截断过长的单元格并去掉换行
+pub fn table_cell(text: &str, max_chars: usize) -> String {
+    let text = text.replace(['\n', '\r'], " ");
+    match text.chars().count() > max_chars {
+        true => format!("{}...", text.chars().take(max_chars).collect::<String>()),
+        false => text,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format_table, table_cell};
+
+    //cargo test commons::table_utile::test::test_format_table -- --nocapture
+    #[test]
+    fn test_format_table() {
+        let headers = vec!["rank".to_string(), "id".to_string()];
+        let rows = vec![
+            vec!["1".to_string(), "abc".to_string()],
+            vec!["10".to_string(), "d".to_string()],
+        ];
+        let table = format_table(&headers, &rows);
+        println!("{}", table);
+        assert_eq!(table, "rank | id\n-----+----\n1    | abc\n10   | d");
+        assert_eq!(table_cell("a\nbcdef", 3), "a b...");
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigAuth {
+    // 开启后 /api 下的请求需携带 Authorization: Bearer <key> 或 X-API-Key: <key>
+    #[serde(default = "ConfigAuth::enable_default")]
+    pub enable: bool,
+    #[serde(default)]
+    pub keys: Vec<ConfigApiKey>,
+}
+
+impl Default for ConfigAuth {
+    fn default() -> Self {
+        Self {
+            enable: Self::enable_default(),
+            keys: vec![],
+        }
+    }
+}
+
+impl ConfigAuth {
+    fn enable_default() -> bool {
+        false
+    }
+
+    /// 按明文 key 查找配置项。hash 按常量时间比较，且遍历全部配置项，耗时与匹配位置无关
+    pub fn find_key(&self, key: &str) -> Option<&ConfigApiKey> {
+        let hash = hash_api_key(key);
+        let mut found = None;
+        for k in self.keys.iter() {
+            let matched =
+                constant_time_eq(k.key_hash.to_ascii_lowercase().as_bytes(), hash.as_bytes());
+            if matched && found.is_none() {
+                found = Some(k);
+            }
+        }
+        found
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigApiKey {
+    pub name: String,
+    // key 的 sha256 hex，可用 echo -n <key> | sha256sum 生成
+    pub key_hash: String,
+    #[serde(default)]
+    pub scopes: Vec<Scope>,
+    // 允许访问的 collection，为空时不限制
+    #[serde(default)]
+    pub collections: Vec<String>,
+}
+
+impl ConfigApiKey {
+    /// admin 拥有全部权限
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes
+            .iter()
+            .any(|s| *s == scope || *s == Scope::Admin)
+    }
+
+    pub fn collection_allowed(&self, collection: &str) -> bool {
+        self.collections.is_empty() || self.collections.iter().any(|c| c == collection)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    // /api/v1/embedding、/api/v1/chunk
+    Embed,
+    // /api/v1/retriever、/api/v1/rerank
+    Retrieve,
+    // /api/v1/answer
+    Generate,
+    // /api/v1/task/*
+    Write,
+    // /api/v1/currentconfig 及全部接口
+    Admin,
+}
+
+pub fn hash_api_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+// 长度不同直接返回，hash 长度固定，不泄露信息
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::{constant_time_eq, hash_api_key, ConfigApiKey, ConfigAuth, Scope};
+
+    //cargo test configure::config_auth::test::test_find_key -- --nocapture
+    #[test]
+    fn test_find_key() {
+        let auth = ConfigAuth {
+            enable: true,
+            keys: vec![ConfigApiKey {
+                name: "reader".to_string(),
+                key_hash: hash_api_key("secret").to_uppercase(),
+                scopes: vec![Scope::Retrieve],
+                collections: vec!["docs".to_string()],
+            }],
+        };
+        println!("{}", hash_api_key("secret"));
+        let key = auth.find_key("secret").unwrap();
+        assert!(key.has_scope(Scope::Retrieve));
+        assert!(!key.has_scope(Scope::Write));
+        assert!(key.collection_allowed("docs"));
+        assert!(!key.collection_allowed("other"));
+        assert!(auth.find_key("wrong").is_none());
+    }
+
+    //cargo test configure::config_auth::test::test_constant_time_eq -- --nocapture
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
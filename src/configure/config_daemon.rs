@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigDaemon {
+    // pid 文件路径，服务运行期间持有文件锁，同一 pid 文件只能启动一个服务
+    #[serde(default = "ConfigDaemon::pid_file_default")]
+    pub pid_file: String,
+    // stop 等待服务退出的秒数，应大于 http.drain_timeout_secs
+    #[serde(default = "ConfigDaemon::stop_timeout_secs_default")]
+    pub stop_timeout_secs: u64,
+}
+
+impl Default for ConfigDaemon {
+    fn default() -> Self {
+        Self {
+            pid_file: Self::pid_file_default(),
+            stop_timeout_secs: Self::stop_timeout_secs_default(),
+        }
+    }
+}
+
+impl ConfigDaemon {
+    fn pid_file_default() -> String {
+        "pid".to_string()
+    }
+    fn stop_timeout_secs_default() -> u64 {
+        60
+    }
+}
@@ -1,10 +1,21 @@
+use super::config_auth::ConfigAuth;
+use super::config_daemon::ConfigDaemon;
+use super::config_ingest::ConfigIngest;
+use super::config_layer::{
+    apply_overrides, build_config, env_overrides, parse_config_file, set_overrides,
+};
+use super::config_log::ConfigLog;
 use super::config_qdrant::ConfigQdrant;
+use super::config_reload::ConfigReload;
+use super::config_reranker::ConfigReranker;
+use super::config_source::{ConfigSources, ValueSource, GLOBAL_CONFIG_SOURCES};
+use super::config_task::ConfigTask;
+use super::config_watch::ConfigWatch;
 use super::{config_http::ConfigHttp, config_model::ConfigModel};
 use crate::configure::config_error::{ConfigError, ConfigErrorType};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use serde_yaml::from_str;
 use std::fs;
 use std::path::Path;
 use std::sync::RwLock;
@@ -22,6 +33,22 @@ pub struct Config {
     pub model: ConfigModel,
     #[serde(default = "ConfigQdrant::default")]
     pub qdrant: ConfigQdrant,
+    #[serde(default = "ConfigReranker::default")]
+    pub reranker: ConfigReranker,
+    #[serde(default = "ConfigIngest::default")]
+    pub ingest: ConfigIngest,
+    #[serde(default = "ConfigWatch::default")]
+    pub watch: ConfigWatch,
+    #[serde(default = "ConfigTask::default")]
+    pub task: ConfigTask,
+    #[serde(default = "ConfigAuth::default")]
+    pub auth: ConfigAuth,
+    #[serde(default = "ConfigReload::default")]
+    pub reload: ConfigReload,
+    #[serde(default = "ConfigDaemon::default")]
+    pub daemon: ConfigDaemon,
+    #[serde(default = "ConfigLog::default")]
+    pub log: ConfigLog,
 }
 
 impl Config {
@@ -30,6 +57,14 @@ impl Config {
             http: ConfigHttp::default(),
             model: ConfigModel::default(),
             qdrant: ConfigQdrant::default(),
+            reranker: ConfigReranker::default(),
+            ingest: ConfigIngest::default(),
+            watch: ConfigWatch::default(),
+            task: ConfigTask::default(),
+            auth: ConfigAuth::default(),
+            reload: ConfigReload::default(),
+            daemon: ConfigDaemon::default(),
+            log: ConfigLog::default(),
         }
     }
 
@@ -49,22 +84,53 @@ pub fn generate_default_config(path: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn set_config(path: &str) {
-    let mut global_config = GLOBAL_CONFIG.write().unwrap();
-    if path.is_empty() {
-        if Path::new("config.yml").exists() {
+/// 加载配置并设为全局配置，path 为空时使用当前目录下的 config.yml（若存在）
+pub fn set_config(path: &str, sets: &[String]) -> Result<()> {
+    let (config, sources) = load_config(path, sets)?;
+    *GLOBAL_CONFIG.write().map_err(|e| anyhow!(e.to_string()))? = config;
+    *GLOBAL_CONFIG_SOURCES
+        .write()
+        .map_err(|e| anyhow!(e.to_string()))? = sources;
+    Ok(())
+}
+
+/// 按默认值、配置文件、环境变量、--set 的顺序加载配置，后者覆盖前者
+pub fn load_config(path: &str, sets: &[String]) -> Result<(Config, ConfigSources)> {
+    let path = match path.is_empty() {
+        true if Path::new("config.yml").exists() => Some("config.yml"),
+        true => None,
+        false => Some(path),
+    };
+
+    let mut sources = ConfigSources {
+        sets: sets.to_vec(),
+        ..Default::default()
+    };
+    let mut value = match path {
+        Some(p) => {
             let contents =
-                fs::read_to_string("config.yml").expect("Read config file config.yml error!");
-            let config = from_str::<Config>(contents.as_str()).expect("Parse config.yml error!");
-            *global_config = config;
+                fs::read_to_string(p).map_err(|e| anyhow!("read config file {}: {}", p, e))?;
+            let value = parse_config_file(p, &contents)?;
+            sources.file = Some(p.to_string());
+            sources.record(&value, ValueSource::File);
+            value
         }
-        return;
-    }
+        None => serde_json::Value::Object(serde_json::Map::new()),
+    };
+
+    let vars = std::env::vars_os()
+        .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)));
+    let env = env_overrides(vars)?;
+    let cli = set_overrides(sets)?;
+    apply_overrides(&mut value, &env);
+    apply_overrides(&mut value, &cli);
+    env.iter()
+        .for_each(|o| sources.record_key(&o.key, ValueSource::Env));
+    cli.iter()
+        .for_each(|o| sources.record_key(&o.key, ValueSource::Cli));
 
-    let err_str = format!("Read config file {} error!", path);
-    let contents = fs::read_to_string(path).expect(err_str.as_str());
-    let config = from_str::<Config>(contents.as_str()).expect("Parse config.yml error!");
-    *global_config = config;
+    let config = build_config(value, &[env, cli].concat())?;
+    Ok((config, sources))
 }
 
 pub fn get_config() -> Result<Config> {
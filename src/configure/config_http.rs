@@ -1,4 +1,6 @@
+use anyhow::{anyhow, Result};
 use serde::Serialize;
+use std::{fmt, net::SocketAddr, path::PathBuf, str::FromStr};
 
 #[derive(Debug, PartialEq, Serialize, serde::Deserialize, Clone)]
 pub struct ConfigHttp {
@@ -6,6 +8,15 @@ pub struct ConfigHttp {
     pub port: u16,
     #[serde(default = "ConfigHttp::bind_default")]
     pub bind: String,
+    // 监听地址列表，如 "0.0.0.0:3000"、"[::1]:3000"、"unix:/tmp/embedding_server.sock"；
+    // 为空时监听 bind:port。"[::]" 在 Linux 上同时接受 IPv4 连接，不要与 "0.0.0.0" 同端口同时配置
+    #[serde(default)]
+    pub listeners: Vec<String>,
+    #[serde(default = "ConfigHttpLimits::default")]
+    pub limits: ConfigHttpLimits,
+    // 收到终止信号后等待处理中请求及后台任务退出的秒数，超时后强制退出
+    #[serde(default = "ConfigHttp::drain_timeout_secs_default")]
+    pub drain_timeout_secs: u64,
 }
 
 impl Default for ConfigHttp {
@@ -13,6 +24,9 @@ impl Default for ConfigHttp {
         Self {
             port: ConfigHttp::port_default(),
             bind: ConfigHttp::bind_default(),
+            listeners: vec![],
+            limits: ConfigHttpLimits::default(),
+            drain_timeout_secs: ConfigHttp::drain_timeout_secs_default(),
         }
     }
 }
@@ -24,4 +38,170 @@ impl ConfigHttp {
     pub fn bind_default() -> String {
         "::0".to_string()
     }
+    pub fn drain_timeout_secs_default() -> u64 {
+        30
+    }
+
+    pub fn listen_addrs(&self) -> Result<Vec<ListenAddr>> {
+        if self.listeners.is_empty() {
+            let ip = self
+                .bind
+                .parse()
+                .map_err(|e| anyhow!("invalid http.bind {}: {}", self.bind, e))?;
+            return Ok(vec![ListenAddr::Tcp(SocketAddr::new(ip, self.port))]);
+        }
+        self.listeners
+            .iter()
+            .map(|l| ListenAddr::from_str(l))
+            .collect()
+    }
+}
+
+/// 请求限制，超时返回 408，请求体过大返回 413，超过并发上限返回 503
+#[derive(Debug, PartialEq, Serialize, serde::Deserialize, Clone)]
+pub struct ConfigHttpLimits {
+    // 请求体字节数上限
+    #[serde(default = "ConfigHttpLimits::max_body_size_default")]
+    pub max_body_size: usize,
+    // /api 下所有路由同时处理的请求数上限，0 为不限制
+    #[serde(default = "ConfigHttpLimits::max_concurrency_default")]
+    pub max_concurrency: usize,
+    // /health 及 /api/v1/currentconfig
+    #[serde(default = "ConfigHttpLimits::health_default")]
+    pub health: ConfigRouteLimit,
+    // /api/v1/embedding、/api/v1/chunk
+    #[serde(default = "ConfigHttpLimits::embedding_default")]
+    pub embedding: ConfigRouteLimit,
+    // /api/v1/retriever、/api/v1/rerank
+    #[serde(default = "ConfigHttpLimits::retrieval_default")]
+    pub retrieval: ConfigRouteLimit,
+    // /api/v1/answer
+    #[serde(default = "ConfigHttpLimits::generation_default")]
+    pub generation: ConfigRouteLimit,
+    // /api/v1/task/*
+    #[serde(default = "ConfigHttpLimits::task_default")]
+    pub task: ConfigRouteLimit,
+}
+
+impl Default for ConfigHttpLimits {
+    fn default() -> Self {
+        Self {
+            max_body_size: Self::max_body_size_default(),
+            max_concurrency: Self::max_concurrency_default(),
+            health: Self::health_default(),
+            embedding: Self::embedding_default(),
+            retrieval: Self::retrieval_default(),
+            generation: Self::generation_default(),
+            task: Self::task_default(),
+        }
+    }
+}
+
+impl ConfigHttpLimits {
+    fn max_body_size_default() -> usize {
+        16 * 1024 * 1024
+    }
+    fn max_concurrency_default() -> usize {
+        512
+    }
+    fn health_default() -> ConfigRouteLimit {
+        ConfigRouteLimit::new(2, 0)
+    }
+    fn embedding_default() -> ConfigRouteLimit {
+        ConfigRouteLimit::new(30, 64)
+    }
+    fn retrieval_default() -> ConfigRouteLimit {
+        ConfigRouteLimit::new(30, 64)
+    }
+    fn generation_default() -> ConfigRouteLimit {
+        ConfigRouteLimit::new(300, 4)
+    }
+    fn task_default() -> ConfigRouteLimit {
+        ConfigRouteLimit::new(10, 32)
+    }
+}
+
+/// 一组路由的限制，max_in_flight 对组内每个路由分别生效
+#[derive(Debug, PartialEq, Serialize, serde::Deserialize, Clone)]
+pub struct ConfigRouteLimit {
+    #[serde(default = "ConfigRouteLimit::timeout_secs_default")]
+    pub timeout_secs: u64,
+    // 单个路由同时处理的请求数上限，0 为不限制
+    #[serde(default)]
+    pub max_in_flight: usize,
+}
+
+impl ConfigRouteLimit {
+    pub fn new(timeout_secs: u64, max_in_flight: usize) -> Self {
+        Self {
+            timeout_secs,
+            max_in_flight,
+        }
+    }
+    fn timeout_secs_default() -> u64 {
+        30
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListenAddr {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl FromStr for ListenAddr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(anyhow!("invalid listener {}: empty socket path", s));
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        let addr = s
+            .parse::<SocketAddr>()
+            .map_err(|e| anyhow!("invalid listener {}: {}", s, e))?;
+        Ok(Self::Tcp(addr))
+    }
+}
+
+impl fmt::Display for ListenAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{}", addr),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ConfigHttp, ListenAddr};
+    use std::{path::PathBuf, str::FromStr};
+
+    //cargo test configure::config_http::test::test_listen_addrs -- --nocapture
+    #[test]
+    fn test_listen_addrs() {
+        let config = ConfigHttp::default();
+        assert_eq!(config.listen_addrs().unwrap()[0].to_string(), "[::]:3000");
+
+        let config = ConfigHttp {
+            listeners: vec![
+                "127.0.0.1:3000".to_string(),
+                "[::1]:3001".to_string(),
+                "unix:/tmp/embedding_server.sock".to_string(),
+            ],
+            ..ConfigHttp::default()
+        };
+        let addrs = config.listen_addrs().unwrap();
+        println!("{:?}", addrs);
+        assert_eq!(addrs[1].to_string(), "[::1]:3001");
+        assert_eq!(
+            addrs[2],
+            ListenAddr::Unix(PathBuf::from("/tmp/embedding_server.sock"))
+        );
+        assert!(ListenAddr::from_str("localhost").is_err());
+        assert!(ListenAddr::from_str("unix:").is_err());
+    }
 }
@@ -0,0 +1,60 @@
+use crate::chunk::Splitter;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigIngest {
+    // 写入 payload 的文本字段名，需与 reranker.text_field 保持一致
+    #[serde(default = "ConfigIngest::text_field_default")]
+    pub text_field: String,
+    // jsonl 文件中每行读取文本的字段
+    #[serde(default = "ConfigIngest::jsonl_text_field_default")]
+    pub jsonl_text_field: String,
+    // jsonl 导入时读取 point id 的字段，缺失时由文本内容生成
+    #[serde(default = "ConfigIngest::jsonl_id_field_default")]
+    pub jsonl_id_field: String,
+    // 参与入库的文件扩展名
+    #[serde(default = "ConfigIngest::extensions_default")]
+    pub extensions: Vec<String>,
+    // 文本切分方式
+    #[serde(default = "Splitter::default")]
+    pub splitter: Splitter,
+    // 每批写入 qdrant 的 point 数量
+    #[serde(default = "ConfigIngest::batch_size_default")]
+    pub batch_size: usize,
+}
+
+impl Default for ConfigIngest {
+    fn default() -> Self {
+        Self {
+            text_field: Self::text_field_default(),
+            jsonl_text_field: Self::jsonl_text_field_default(),
+            jsonl_id_field: Self::jsonl_id_field_default(),
+            extensions: Self::extensions_default(),
+            splitter: Splitter::default(),
+            batch_size: Self::batch_size_default(),
+        }
+    }
+}
+
+impl ConfigIngest {
+    fn text_field_default() -> String {
+        "content".to_string()
+    }
+    fn jsonl_text_field_default() -> String {
+        "content".to_string()
+    }
+    fn jsonl_id_field_default() -> String {
+        "id".to_string()
+    }
+    fn extensions_default() -> Vec<String> {
+        vec![
+            "txt".to_string(),
+            "md".to_string(),
+            "markdown".to_string(),
+            "jsonl".to_string(),
+        ]
+    }
+    fn batch_size_default() -> usize {
+        64
+    }
+}
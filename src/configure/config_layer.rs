@@ -0,0 +1,194 @@
+use super::Config;
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+/// 环境变量前缀，层级以 "__" 分隔，如 EMBEDDING_SERVER__QDRANT__URI 对应 qdrant.uri
+pub const ENV_PREFIX: &str = "EMBEDDING_SERVER__";
+
+/// 一个来自环境变量或命令行的配置覆盖
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigOverride {
+    pub key: String,
+    pub value: Value,
+    // 出错时提示来源，如 "env EMBEDDING_SERVER__QDRANT__URI"
+    pub origin: String,
+}
+
+/// 解析配置文件，语法及类型错误带有文件行列号及 key 路径
+pub fn parse_config_file(path: &str, contents: &str) -> Result<Value> {
+    let de = serde_yaml::Deserializer::from_str(contents);
+    serde_path_to_error::deserialize::<_, Config>(de).map_err(|e| {
+        // serde_yaml 的类型错误已带有 key 路径
+        let inner = e.inner().to_string();
+        match inner.starts_with(&e.path().to_string()) {
+            true => anyhow!("config file {}: key {}", path, inner),
+            false => anyhow!("config file {}: {}", path, describe(e.path(), e.inner())),
+        }
+    })?;
+    let value = serde_yaml::from_str::<Value>(contents)
+        .map_err(|e| anyhow!("config file {}: {}", path, e))?;
+    match value {
+        Value::Null => Ok(Value::Object(Map::new())),
+        v => Ok(v),
+    }
+}
+
+/// 读取 EMBEDDING_SERVER__ 开头的环境变量
+pub fn env_overrides(vars: impl Iterator<Item = (String, String)>) -> Result<Vec<ConfigOverride>> {
+    let mut overrides = vec![];
+    for (name, raw) in vars {
+        let path = match name.strip_prefix(ENV_PREFIX) {
+            Some(p) => p,
+            None => continue,
+        };
+        let key = path
+            .split("__")
+            .map(|s| s.to_lowercase())
+            .collect::<Vec<String>>()
+            .join(".");
+        overrides.push(new_override(key, &raw, format!("env {}", name))?);
+    }
+    overrides.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(overrides)
+}
+
+/// 解析 --set key=value
+pub fn set_overrides(sets: &[String]) -> Result<Vec<ConfigOverride>> {
+    let mut overrides = vec![];
+    for set in sets {
+        let (key, raw) = set
+            .split_once('=')
+            .ok_or_else(|| anyhow!("--set {}: expected key=value", set))?;
+        overrides.push(new_override(
+            key.trim().to_string(),
+            raw,
+            format!("--set {}", set),
+        )?);
+    }
+    Ok(overrides)
+}
+
+// key 必须是已有配置项，值按默认配置中该项的类型解析
+fn new_override(key: String, raw: &str, origin: String) -> Result<ConfigOverride> {
+    let defaults = serde_json::to_value(Config::default())?;
+    let current =
+        lookup(&defaults, &key).ok_or_else(|| anyhow!("{}: unknown config key {}", origin, key))?;
+    let value = match current {
+        Value::String(_) => Value::String(raw.to_string()),
+        _ => {
+            let parsed = serde_yaml::from_str::<Value>(raw)
+                .unwrap_or_else(|_| Value::String(raw.to_string()));
+            match (current, &parsed) {
+                // Option<String> 类的配置项，数字或布尔值按字符串处理
+                (Value::Null, Value::Number(_) | Value::Bool(_)) => Value::String(raw.to_string()),
+                _ => parsed,
+            }
+        }
+    };
+    Ok(ConfigOverride { key, value, origin })
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |v, k| v.get(k))
+}
+
+/// 将覆盖项写入配置树
+pub fn apply_overrides(base: &mut Value, overrides: &[ConfigOverride]) {
+    for o in overrides {
+        let mut node = &mut *base;
+        let segments = o.key.split('.').collect::<Vec<&str>>();
+        for segment in &segments[..segments.len() - 1] {
+            if !node.is_object() {
+                *node = Value::Object(Map::new());
+            }
+            node = node
+                .as_object_mut()
+                .unwrap()
+                .entry(segment.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+        }
+        if !node.is_object() {
+            *node = Value::Object(Map::new());
+        }
+        node.as_object_mut()
+            .unwrap()
+            .insert(segments[segments.len() - 1].to_string(), o.value.clone());
+    }
+}
+
+/// 合并后的配置树反序列化为 Config，类型错误时指出 key 路径及覆盖来源
+pub fn build_config(value: Value, overrides: &[ConfigOverride]) -> Result<Config> {
+    serde_path_to_error::deserialize::<_, Config>(value).map_err(|e| {
+        let path = e.path().to_string();
+        let msg = describe(e.path(), e.inner());
+        let from = overrides
+            .iter()
+            .rev()
+            .find(|o| path == o.key || path.starts_with(&format!("{}.", o.key)));
+        match from {
+            Some(o) => anyhow!("{}: {}", o.origin, msg),
+            None => anyhow!("{}", msg),
+        }
+    })
+}
+
+fn describe(path: &serde_path_to_error::Path, inner: &impl std::fmt::Display) -> String {
+    match path.to_string().as_str() {
+        "." | "?" => inner.to_string(),
+        p => format!("key {}: {}", p, inner),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply_overrides, build_config, env_overrides, parse_config_file, set_overrides};
+
+    //cargo test configure::config_layer::test::test_layered_config -- --nocapture
+    #[test]
+    fn test_layered_config() {
+        let mut value = parse_config_file(
+            "config.yml",
+            "http:\n  port: 3001\nqdrant:\n  uri: http://file:6334\n",
+        )
+        .unwrap();
+        let env = env_overrides(
+            vec![
+                (
+                    "EMBEDDING_SERVER__QDRANT__URI".to_string(),
+                    "http://env:6334".to_string(),
+                ),
+                (
+                    "EMBEDDING_SERVER__QDRANT__API_KEY".to_string(),
+                    "123".to_string(),
+                ),
+                ("PATH".to_string(), "/bin".to_string()),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let cli = set_overrides(&["http.port=3002".to_string()]).unwrap();
+        apply_overrides(&mut value, &env);
+        apply_overrides(&mut value, &cli);
+        let config = build_config(value, &[env, cli].concat()).unwrap();
+        assert_eq!(config.http.port, 3002);
+        assert_eq!(config.qdrant.uri, "http://env:6334");
+        assert_eq!(config.qdrant.api_key, Some("123".to_string()));
+
+        let err = parse_config_file("config.yml", "http:\n  port: abc\n").unwrap_err();
+        println!("{}", err);
+        assert!(err.to_string().contains("key http.port"));
+        assert!(err.to_string().contains("line 2"));
+
+        let err = set_overrides(&["qdrant.uir=x".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("unknown config key qdrant.uir"));
+
+        let mut value = parse_config_file("config.yml", "").unwrap();
+        let cli = set_overrides(&["http.port=abc".to_string()]).unwrap();
+        apply_overrides(&mut value, &cli);
+        let err = build_config(value, &cli).unwrap_err();
+        println!("{}", err);
+        assert!(err
+            .to_string()
+            .starts_with("--set http.port=abc: key http.port"));
+    }
+}
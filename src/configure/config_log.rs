@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Pretty,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    Daily,
+    Never,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigLog {
+    // pretty 或 json，同时作用于终端及文件输出
+    #[serde(default = "ConfigLog::format_default")]
+    pub format: LogFormat,
+    // trace、debug、info、warn、error 或 off
+    #[serde(default = "ConfigLog::level_default")]
+    pub level: String,
+    #[serde(default = "ConfigLog::dir_default")]
+    pub dir: String,
+    // 日志文件名前缀，按 rotation 追加日期后缀
+    #[serde(default = "ConfigLog::file_prefix_default")]
+    pub file_prefix: String,
+    #[serde(default = "ConfigLog::rotation_default")]
+    pub rotation: LogRotation,
+}
+
+impl Default for ConfigLog {
+    fn default() -> Self {
+        Self {
+            format: Self::format_default(),
+            level: Self::level_default(),
+            dir: Self::dir_default(),
+            file_prefix: Self::file_prefix_default(),
+            rotation: Self::rotation_default(),
+        }
+    }
+}
+
+impl ConfigLog {
+    fn format_default() -> LogFormat {
+        LogFormat::Pretty
+    }
+    fn level_default() -> String {
+        "info".to_string()
+    }
+    fn dir_default() -> String {
+        "logs".to_string()
+    }
+    fn file_prefix_default() -> String {
+        "embedding_server.log".to_string()
+    }
+    fn rotation_default() -> LogRotation {
+        LogRotation::Daily
+    }
+}
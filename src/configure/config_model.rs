@@ -10,6 +10,9 @@ pub struct ConfigModel {
     pub use_pth: bool,
     #[serde(default = "ConfigModel::approximate_gelu_default")]
     pub approximate_gelu: bool,
+    // 推理设备：cpu、cuda、cuda:N、metal、metal:N，embedding 与 reranker 共用
+    #[serde(default = "ConfigModel::device_default")]
+    pub device: String,
 }
 
 impl Default for ConfigModel {
@@ -19,6 +22,7 @@ impl Default for ConfigModel {
             revision: Self::revision_default(),
             use_pth: Self::use_pth_default(),
             approximate_gelu: Self::approximate_gelu_default(),
+            device: Self::device_default(),
         }
     }
 }
@@ -36,4 +40,7 @@ impl ConfigModel {
     fn approximate_gelu_default() -> bool {
         false
     }
+    fn device_default() -> String {
+        "cuda:0".to_string()
+    }
 }
@@ -0,0 +1,195 @@
+use super::{
+    config_layer::{apply_overrides, build_config, ConfigOverride},
+    config_source::{flatten, GLOBAL_CONFIG_SOURCES},
+    get_config, load_config, GLOBAL_CONFIG,
+};
+use crate::logger::set_log_level;
+use crate::resources::resource_qdrant::rebuild_qdrant_client;
+use anyhow::{anyhow, Result};
+use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, RecvTimeoutError},
+    time::Duration,
+};
+
+// 运行中可直接生效的配置项，其余变更需重启
+const HOT_RELOAD_KEYS: [&str; 8] = [
+    "qdrant",
+    "ingest",
+    "auth",
+    "reranker.top_n",
+    "reranker.text_field",
+    "task.save_interval_secs",
+    "task.file_root",
+    "log.level",
+];
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigReload {
+    // 监听配置文件，文件变化后自动重新加载
+    #[serde(default = "ConfigReload::watch_file_default")]
+    pub watch_file: bool,
+    #[serde(default = "ConfigReload::debounce_ms_default")]
+    pub debounce_ms: u64,
+}
+
+impl Default for ConfigReload {
+    fn default() -> Self {
+        Self {
+            watch_file: Self::watch_file_default(),
+            debounce_ms: Self::debounce_ms_default(),
+        }
+    }
+}
+
+impl ConfigReload {
+    fn watch_file_default() -> bool {
+        false
+    }
+    fn debounce_ms_default() -> u64 {
+        500
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReloadReport {
+    // 已生效的配置项
+    pub applied: Vec<String>,
+    // 已变更但需重启才能生效的配置项
+    pub restart_required: Vec<String>,
+    pub qdrant_rebuilt: bool,
+}
+
+fn hot_reloadable(key: &str) -> bool {
+    HOT_RELOAD_KEYS
+        .iter()
+        .any(|k| key == *k || key.starts_with(&format!("{}.", k)))
+}
+
+/// 以启动时的配置文件及 --set 参数重新加载配置，只应用可热加载的配置项。
+/// 新配置有误时保持原配置不变
+pub fn reload_config() -> Result<ReloadReport> {
+    let (file, sets) = {
+        let sources = GLOBAL_CONFIG_SOURCES
+            .read()
+            .map_err(|e| anyhow!(e.to_string()))?;
+        (
+            sources.file.clone().unwrap_or_default(),
+            sources.sets.clone(),
+        )
+    };
+    let (new_config, new_sources) = load_config(&file, &sets)?;
+    let old = serde_json::to_value(get_config()?)?;
+    let old_flat = flatten(&old)
+        .into_iter()
+        .collect::<BTreeMap<String, Value>>();
+    let new_flat = flatten(&serde_json::to_value(&new_config)?)
+        .into_iter()
+        .collect::<BTreeMap<String, Value>>();
+
+    let mut report = ReloadReport::default();
+    let mut overrides = vec![];
+    for (key, value) in &new_flat {
+        if old_flat.get(key) == Some(value) {
+            continue;
+        }
+        match hot_reloadable(key) {
+            true => {
+                overrides.push(ConfigOverride {
+                    key: key.clone(),
+                    value: value.clone(),
+                    origin: "reload".to_string(),
+                });
+                report.applied.push(key.clone());
+            }
+            false => report.restart_required.push(key.clone()),
+        }
+    }
+
+    let mut merged = old;
+    apply_overrides(&mut merged, &overrides);
+    let config = build_config(merged, &overrides)?;
+    if report.applied.iter().any(|k| k == "log.level") {
+        set_log_level(&config.log.level)?;
+    }
+    if report.applied.iter().any(|k| k.starts_with("qdrant.")) {
+        rebuild_qdrant_client(&config.qdrant)?;
+        report.qdrant_rebuilt = true;
+    }
+    *GLOBAL_CONFIG.write().map_err(|e| anyhow!(e.to_string()))? = config;
+    // 只更新已生效配置项的来源，需重启的配置项仍按当前运行值展示来源
+    let mut sources = GLOBAL_CONFIG_SOURCES
+        .write()
+        .map_err(|e| anyhow!(e.to_string()))?;
+    for key in &report.applied {
+        sources
+            .sources
+            .insert(key.clone(), new_sources.source_of(key));
+    }
+    drop(sources);
+
+    log::info!(
+        "config reloaded, applied: {:?}, restart required: {:?}",
+        report.applied,
+        report.restart_required
+    );
+    Ok(report)
+}
+
+/// 监听配置文件所在目录，编辑器以重命名方式保存时也能触发。阻塞当前线程
+pub fn watch_config_file(file: &str, debounce_ms: u64) -> Result<()> {
+    let path = std::fs::canonicalize(file)?;
+    let dir = path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or(PathBuf::from("."));
+    let (tx, rx) = channel();
+    let mut watcher = RecommendedWatcher::new(tx, Config::default())?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    log::info!("watching config file {}", path.display());
+
+    let debounce = Duration::from_millis(debounce_ms);
+    let mut pending = false;
+    loop {
+        let received = match pending {
+            true => rx.recv_timeout(debounce),
+            false => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(Ok(event)) => {
+                let modified =
+                    !matches!(event.kind, EventKind::Access(_)) && event.paths.contains(&path);
+                pending = pending || modified;
+            }
+            Ok(Err(e)) => log::error!("watch config file error: {}", e),
+            Err(RecvTimeoutError::Timeout) => {
+                pending = false;
+                if let Err(e) = reload_config() {
+                    log::error!("reload config error: {}", e);
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::hot_reloadable;
+
+    //cargo test configure::config_reload::test::test_hot_reloadable -- --nocapture
+    #[test]
+    fn test_hot_reloadable() {
+        assert!(hot_reloadable("qdrant.timeout"));
+        assert!(hot_reloadable("auth.keys"));
+        assert!(hot_reloadable("reranker.top_n"));
+        assert!(!hot_reloadable("reranker.enable"));
+        assert!(!hot_reloadable("model.model_id"));
+        assert!(!hot_reloadable("qdrant_extra"));
+        assert!(!hot_reloadable("http.port"));
+    }
+}
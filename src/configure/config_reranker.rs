@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigReranker {
+    #[serde(default = "ConfigReranker::enable_default")]
+    pub enable: bool,
+    #[serde(default = "ConfigReranker::model_id_default")]
+    pub model_id: String,
+    #[serde(default = "ConfigReranker::revision_default")]
+    pub revision: String,
+    #[serde(default = "ConfigReranker::use_pth_default")]
+    pub use_pth: bool,
+    // 参与重排的候选数量
+    #[serde(default = "ConfigReranker::top_n_default")]
+    pub top_n: u64,
+    // payload 中用于重排的文本字段
+    #[serde(default = "ConfigReranker::text_field_default")]
+    pub text_field: String,
+    #[serde(default = "ConfigReranker::max_length_default")]
+    pub max_length: usize,
+}
+
+impl Default for ConfigReranker {
+    fn default() -> Self {
+        Self {
+            enable: Self::enable_default(),
+            model_id: Self::model_id_default(),
+            revision: Self::revision_default(),
+            use_pth: Self::use_pth_default(),
+            top_n: Self::top_n_default(),
+            text_field: Self::text_field_default(),
+            max_length: Self::max_length_default(),
+        }
+    }
+}
+
+impl ConfigReranker {
+    fn enable_default() -> bool {
+        false
+    }
+    fn model_id_default() -> String {
+        "BAAI/bge-reranker-base".to_string()
+    }
+    fn revision_default() -> String {
+        "main".to_string()
+    }
+    fn use_pth_default() -> bool {
+        false
+    }
+    fn top_n_default() -> u64 {
+        20
+    }
+    fn text_field_default() -> String {
+        "content".to_string()
+    }
+    fn max_length_default() -> usize {
+        512
+    }
+}
@@ -0,0 +1,189 @@
+use super::Config;
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// 当前配置的来源，由 set_config 记录
+pub static GLOBAL_CONFIG_SOURCES: Lazy<RwLock<ConfigSources>> =
+    Lazy::new(|| RwLock::new(ConfigSources::default()));
+
+const REDACTED: &str = "******";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueSource {
+    Default,
+    File,
+    Env,
+    Cli,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigSources {
+    // 加载的配置文件路径，未使用配置文件时为空
+    pub file: Option<String>,
+    // 命令行 --set 参数，重新加载配置时沿用
+    #[serde(default)]
+    pub sets: Vec<String>,
+    // 非默认值的来源，key 为 "qdrant.uri" 形式的路径
+    pub sources: BTreeMap<String, ValueSource>,
+}
+
+impl ConfigSources {
+    /// 记录 value 中每个叶子节点的来源，后记录的覆盖先记录的
+    pub fn record(&mut self, value: &Value, source: ValueSource) {
+        for (key, _) in flatten(value) {
+            self.sources.insert(key, source);
+        }
+    }
+
+    /// 记录整个 key 的来源，覆盖其下级路径已记录的来源
+    pub fn record_key(&mut self, key: &str, source: ValueSource) {
+        let prefix = format!("{}.", key);
+        self.sources.retain(|k, _| !k.starts_with(&prefix));
+        self.sources.insert(key.to_string(), source);
+    }
+
+    /// 未记录的 key 按其最近的上级路径取来源，均未记录时为默认值
+    pub fn source_of(&self, key: &str) -> ValueSource {
+        let mut path = key;
+        loop {
+            if let Some(s) = self.sources.get(path) {
+                return *s;
+            }
+            match path.rfind('.') {
+                Some(idx) => path = &path[..idx],
+                None => return ValueSource::Default,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: Value,
+    pub source: ValueSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigInspect {
+    pub file: Option<String>,
+    pub entries: Vec<ConfigEntry>,
+}
+
+/// 脱敏后的配置
+pub fn redacted_config(config: &Config) -> Result<Value> {
+    let mut value = serde_json::to_value(config)?;
+    redact(&mut value);
+    Ok(value)
+}
+
+/// 逐项列出脱敏后的配置值及其来源
+pub fn inspect_config(config: &Config, sources: &ConfigSources) -> Result<ConfigInspect> {
+    let entries = flatten(&redacted_config(config)?)
+        .into_iter()
+        .map(|(key, value)| ConfigEntry {
+            source: sources.source_of(&key),
+            key,
+            value,
+        })
+        .collect();
+    Ok(ConfigInspect {
+        file: sources.file.clone(),
+        entries,
+    })
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key == "api_key"
+        || key == "key_hash"
+        || key == "token"
+        || key.ends_with("_token")
+        || key.contains("password")
+        || key.contains("secret")
+}
+
+/// 将密钥类字段替换为 ******，空值保留以便区分是否已配置
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if is_secret_key(k) && !v.is_null() {
+                    *v = Value::String(REDACTED.to_string());
+                } else {
+                    redact(v);
+                }
+            }
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// 按 "a.b.c" 路径展开对象，数组作为整体的值
+pub fn flatten(value: &Value) -> Vec<(String, Value)> {
+    let mut entries = vec![];
+    flatten_into("", value, &mut entries);
+    entries
+}
+
+fn flatten_into(prefix: &str, value: &Value, entries: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                let key = match prefix.is_empty() {
+                    true => k.clone(),
+                    false => format!("{}.{}", prefix, k),
+                };
+                flatten_into(&key, v, entries);
+            }
+        }
+        _ if !prefix.is_empty() => entries.push((prefix.to_string(), value.clone())),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{flatten, redact, ConfigSources, ValueSource};
+    use serde_json::json;
+
+    //cargo test configure::config_source::test::test_redact_and_source -- --nocapture
+    #[test]
+    fn test_redact_and_source() {
+        let mut value = json!({
+            "qdrant": {"uri": "http://localhost:6334", "api_key": "abc"},
+            "auth": {"keys": [{"name": "r", "key_hash": "0f"}]},
+            "http": {"port": 3000}
+        });
+        redact(&mut value);
+        println!("{}", value);
+        assert_eq!(value["qdrant"]["api_key"], "******");
+        assert_eq!(value["auth"]["keys"][0]["key_hash"], "******");
+        assert_eq!(value["auth"]["keys"][0]["name"], "r");
+
+        let keys = flatten(&value)
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            keys,
+            vec!["auth.keys", "http.port", "qdrant.api_key", "qdrant.uri"]
+        );
+
+        let mut sources = ConfigSources::default();
+        sources.record(
+            &json!({"qdrant": {"uri": "x"}, "auth": {"keys": []}}),
+            ValueSource::File,
+        );
+        sources.record(&json!({"qdrant": {"uri": "y"}}), ValueSource::Env);
+        assert_eq!(sources.source_of("qdrant.uri"), ValueSource::Env);
+        assert_eq!(sources.source_of("auth.keys"), ValueSource::File);
+        assert_eq!(sources.source_of("http.port"), ValueSource::Default);
+    }
+}
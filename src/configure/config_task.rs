@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigTask {
+    // 任务状态持久化目录
+    #[serde(default = "ConfigTask::meta_dir_default")]
+    pub meta_dir: String,
+    // 运行中任务进度落盘间隔
+    #[serde(default = "ConfigTask::save_interval_secs_default")]
+    pub save_interval_secs: u64,
+    // http 接口提交的任务只能读写该目录下的文件，相对路径相对于该目录
+    #[serde(default = "ConfigTask::file_root_default")]
+    pub file_root: String,
+}
+
+impl Default for ConfigTask {
+    fn default() -> Self {
+        Self {
+            meta_dir: Self::meta_dir_default(),
+            save_interval_secs: Self::save_interval_secs_default(),
+            file_root: Self::file_root_default(),
+        }
+    }
+}
+
+impl ConfigTask {
+    fn meta_dir_default() -> String {
+        "task_meta".to_string()
+    }
+    fn save_interval_secs_default() -> u64 {
+        5
+    }
+    fn file_root_default() -> String {
+        "data".to_string()
+    }
+}
@@ -0,0 +1,171 @@
+use super::{
+    config_http::{ConfigHttp, ListenAddr},
+    Config,
+};
+use crate::{
+    embedding::{model_hidden_size, parse_device},
+    resources::resource_qdrant::{collection_dimension, new_qdrant_client},
+};
+use axum::http::Uri;
+use serde::Serialize;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+impl CheckResult {
+    pub fn ok(name: &str, message: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: true,
+            message: message.to_string(),
+        }
+    }
+    pub fn fail(name: &str, message: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: false,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// 语义检查：监听地址、端口占用、qdrant uri、设备、模型及 collection 维度。
+/// 会访问 hf-hub 及 qdrant
+pub async fn validate_config(config: &Config) -> Vec<CheckResult> {
+    let mut results = check_listeners(&config.http);
+    results.push(check_qdrant_uri(&config.qdrant.uri));
+    results.push(match parse_device(&config.model.device) {
+        Ok(_) => CheckResult::ok("device", &config.model.device),
+        Err(e) => CheckResult::fail("device", format!("{}: {}", config.model.device, e)),
+    });
+
+    results.push(
+        match tracing_subscriber::filter::LevelFilter::from_str(&config.log.level) {
+            Ok(_) => CheckResult::ok("log.level", &config.log.level),
+            Err(e) => CheckResult::fail("log.level", format!("{}: {}", config.log.level, e)),
+        },
+    );
+
+    let dimension = match model_hidden_size(&config.model.model_id, &config.model.revision).await {
+        Ok(d) => {
+            results.push(CheckResult::ok(
+                "model",
+                format!("{} dimension {}", config.model.model_id, d),
+            ));
+            Some(d as u64)
+        }
+        Err(e) => {
+            results.push(CheckResult::fail(
+                "model",
+                format!(
+                    "{} revision {}: {}",
+                    config.model.model_id, config.model.revision, e
+                ),
+            ));
+            None
+        }
+    };
+    if config.reranker.enable {
+        let reranker = &config.reranker;
+        results.push(
+            match model_hidden_size(&reranker.model_id, &reranker.revision).await {
+                Ok(_) => CheckResult::ok("reranker", &reranker.model_id),
+                Err(e) => CheckResult::fail("reranker", format!("{}: {}", reranker.model_id, e)),
+            },
+        );
+    }
+
+    if results.iter().any(|r| r.name == "qdrant.uri" && !r.ok) {
+        return results;
+    }
+    results.push(check_collection(config, dimension).await);
+    results
+}
+
+fn check_listeners(http: &ConfigHttp) -> Vec<CheckResult> {
+    let addrs = match http.listen_addrs() {
+        Ok(a) => a,
+        Err(e) => return vec![CheckResult::fail("listen", e)],
+    };
+    addrs
+        .iter()
+        .map(|addr| match addr {
+            ListenAddr::Tcp(socket_addr) => match std::net::TcpListener::bind(socket_addr) {
+                Ok(_) => CheckResult::ok("listen", format!("{} is free", addr)),
+                Err(e) => CheckResult::fail("listen", format!("{}: {}", addr, e)),
+            },
+            ListenAddr::Unix(path) => {
+                if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                    return CheckResult::fail("listen", format!("{} is in use", addr));
+                }
+                match path.parent() {
+                    Some(p) if !p.as_os_str().is_empty() && !p.exists() => CheckResult::fail(
+                        "listen",
+                        format!("{}: directory {} not exist", addr, p.display()),
+                    ),
+                    _ => CheckResult::ok("listen", format!("{} is free", addr)),
+                }
+            }
+        })
+        .collect()
+}
+
+fn check_qdrant_uri(uri: &str) -> CheckResult {
+    let parsed = match uri.parse::<Uri>() {
+        Ok(u) => u,
+        Err(e) => return CheckResult::fail("qdrant.uri", format!("{}: {}", uri, e)),
+    };
+    match (parsed.scheme_str(), parsed.host()) {
+        (Some("http" | "https"), Some(h)) if !h.is_empty() => CheckResult::ok("qdrant.uri", uri),
+        _ => CheckResult::fail(
+            "qdrant.uri",
+            format!("{}: expected http://host:port or https://host:port", uri),
+        ),
+    }
+}
+
+// collection 不存在时入库会按模型维度创建，不视为错误
+async fn check_collection(config: &Config, dimension: Option<u64>) -> CheckResult {
+    let collection = &config.qdrant.collection;
+    let client = match new_qdrant_client(&config.qdrant) {
+        Ok(c) => c,
+        Err(e) => return CheckResult::fail("collection", e),
+    };
+    match (collection_dimension(&client, collection).await, dimension) {
+        (Err(e), _) => CheckResult::fail("collection", format!("{}: {}", collection, e)),
+        (Ok(None), _) => CheckResult::ok(
+            "collection",
+            format!("{} not exist, will be created on ingest", collection),
+        ),
+        (Ok(Some(d)), Some(m)) if d != m => CheckResult::fail(
+            "collection",
+            format!(
+                "{} dimension {} does not match model dimension {}",
+                collection, d, m
+            ),
+        ),
+        (Ok(Some(d)), _) => {
+            CheckResult::ok("collection", format!("{} dimension {}", collection, d))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::check_qdrant_uri;
+
+    //cargo test configure::config_validate::test::test_check_qdrant_uri -- --nocapture
+    #[test]
+    fn test_check_qdrant_uri() {
+        assert!(check_qdrant_uri("http://localhost:6334").ok);
+        assert!(check_qdrant_uri("https://qdrant.example.com").ok);
+        assert!(!check_qdrant_uri("localhost:6334").ok);
+        assert!(!check_qdrant_uri("grpc://localhost:6334").ok);
+        assert!(!check_qdrant_uri("http://").ok);
+    }
+}
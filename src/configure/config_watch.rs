@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ConfigWatch {
+    #[serde(default = "ConfigWatch::enable_default")]
+    pub enable: bool,
+    // 与向量库保持同步的目录
+    #[serde(default = "ConfigWatch::folder_default")]
+    pub folder: String,
+    // 文件事件静默该时长后才触发重建索引
+    #[serde(default = "ConfigWatch::debounce_ms_default")]
+    pub debounce_ms: u64,
+    // 持续有文件事件时，距第一个未处理事件最长等待该时长即触发重建
+    #[serde(default = "ConfigWatch::max_wait_ms_default")]
+    pub max_wait_ms: u64,
+    #[serde(default = "ConfigWatch::regex_default")]
+    pub include_regex: Option<Vec<String>>,
+    #[serde(default = "ConfigWatch::regex_default")]
+    pub exclude_regex: Option<Vec<String>>,
+}
+
+impl Default for ConfigWatch {
+    fn default() -> Self {
+        Self {
+            enable: Self::enable_default(),
+            folder: Self::folder_default(),
+            debounce_ms: Self::debounce_ms_default(),
+            max_wait_ms: Self::max_wait_ms_default(),
+            include_regex: Self::regex_default(),
+            exclude_regex: Self::regex_default(),
+        }
+    }
+}
+
+impl ConfigWatch {
+    fn enable_default() -> bool {
+        false
+    }
+    fn folder_default() -> String {
+        "docs".to_string()
+    }
+    fn debounce_ms_default() -> u64 {
+        1000
+    }
+    fn max_wait_ms_default() -> u64 {
+        10000
+    }
+    fn regex_default() -> Option<Vec<String>> {
+        None
+    }
+}
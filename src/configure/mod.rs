@@ -1,7 +1,18 @@
 mod config_error;
 mod config_global;
+pub mod config_auth;
+pub mod config_daemon;
 pub mod config_http;
+pub mod config_ingest;
+pub mod config_layer;
+pub mod config_log;
 pub mod config_model;
 pub mod config_qdrant;
+pub mod config_reload;
+pub mod config_reranker;
 pub mod config_rocksdb;
+pub mod config_source;
+pub mod config_task;
+pub mod config_validate;
+pub mod config_watch;
 pub use config_global::*;
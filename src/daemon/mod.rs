@@ -0,0 +1,5 @@
+mod pid_file;
+mod server_state;
+
+pub use pid_file::*;
+pub use server_state::*;
@@ -0,0 +1,123 @@
+use super::state_file_path;
+use anyhow::{anyhow, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+/// 服务运行期间持有的 pid 文件，进程退出后文件锁自动释放，Drop 时删除 pid 文件及状态文件
+pub struct PidFile {
+    path: PathBuf,
+    // 持有文件以保持锁
+    _file: File,
+}
+
+impl PidFile {
+    /// 创建 pid 文件并加排他锁，已被运行中的服务锁定时返回错误
+    pub fn acquire(path: &str) -> Result<Self> {
+        let path = PathBuf::from(path);
+        if let Some(p) = path.parent() {
+            if !p.as_os_str().is_empty() {
+                fs::create_dir_all(p)?;
+            }
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|e| anyhow!("open pid file {} error: {}", path.display(), e))?;
+        if !try_lock(&file) {
+            let pid = read_pid(&path).ok().flatten();
+            return Err(anyhow!(
+                "server already running, pid {}, pid file {}",
+                pid.map_or("-".to_string(), |p| p.to_string()),
+                path.display()
+            ));
+        }
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(file, "{}", std::process::id())?;
+        file.flush()?;
+        Ok(Self { path, _file: file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(state_file_path(&self.path));
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn try_lock(file: &File) -> bool {
+    unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) == 0 }
+}
+
+/// 读取 pid 文件，文件不存在时返回 None
+pub fn read_pid(path: &Path) -> Result<Option<i32>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    let pid = content
+        .trim()
+        .parse::<i32>()
+        .map_err(|e| anyhow!("invalid pid file {}: {}", path.display(), e))?;
+    Ok(Some(pid))
+}
+
+/// pid 文件是否被运行中的服务锁定，未锁定的 pid 文件为上次异常退出遗留
+pub fn pid_file_locked(path: &Path) -> bool {
+    match File::open(path) {
+        // 能加锁说明没有进程持有该文件，关闭文件即释放锁
+        Ok(file) => !try_lock(&file),
+        Err(_) => false,
+    }
+}
+
+pub fn process_alive(pid: i32) -> bool {
+    // 信号 0 只检查进程是否存在及权限
+    let ret = unsafe { libc::kill(pid, 0) };
+    ret == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+pub fn send_signal(pid: i32, signal: i32) -> Result<()> {
+    match unsafe { libc::kill(pid, signal) } {
+        0 => Ok(()),
+        _ => Err(anyhow!(
+            "send signal {} to {} error: {}",
+            signal,
+            pid,
+            std::io::Error::last_os_error()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{pid_file_locked, read_pid, PidFile};
+    use std::path::Path;
+
+    //cargo test daemon::pid_file::test::test_pid_file -- --nocapture
+    #[test]
+    fn test_pid_file() {
+        let path =
+            std::env::temp_dir().join(format!("embedding_server_{}.pid", std::process::id()));
+        let path_str = path.display().to_string();
+        let pid_file = PidFile::acquire(&path_str).unwrap();
+        assert_eq!(read_pid(&path).unwrap(), Some(std::process::id() as i32));
+        assert!(pid_file_locked(&path));
+        let err = PidFile::acquire(&path_str).err().unwrap();
+        println!("{}", err);
+        assert!(err.to_string().contains("already running"));
+        drop(pid_file);
+        assert!(!Path::new(&path).exists());
+        assert!(!pid_file_locked(&path));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::commons::{read_yaml_file, struct_to_yml_file};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 运行中服务的状态，启动完成后写入 pid 文件旁的 .state 文件，供 status 命令读取
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerState {
+    pub pid: u32,
+    pub started_at: u64,
+    pub config_file: Option<String>,
+    pub model: String,
+    pub reranker: Option<String>,
+    pub generation_model: String,
+    pub listeners: Vec<String>,
+}
+
+pub fn state_file_path(pid_file: &Path) -> PathBuf {
+    let mut path = pid_file.as_os_str().to_owned();
+    path.push(".state");
+    PathBuf::from(path)
+}
+
+impl ServerState {
+    pub fn save(&self, pid_file: &Path) -> Result<()> {
+        struct_to_yml_file(self, &state_file_path(pid_file).display().to_string())
+    }
+
+    pub fn load(pid_file: &Path) -> Result<Self> {
+        read_yaml_file::<ServerState>(&state_file_path(pid_file).display().to_string())
+    }
+}
+
+/// 格式化运行时长，如 "1d 2h 3m 4s"
+pub fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes, seconds) = (
+        secs / 86400,
+        secs % 86400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    );
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", seconds),
+        (0, 0, _) => format!("{}m {}s", minutes, seconds),
+        (0, _, _) => format!("{}h {}m {}s", hours, minutes, seconds),
+        _ => format!("{}d {}h {}m {}s", days, hours, minutes, seconds),
+    }
+}
@@ -7,10 +7,13 @@ use candle_transformers::models::qwen2_moe::{Config as ConfigMoe, Model as Model
 use hf_hub::api::tokio::ApiBuilder;
 use hf_hub::{Repo, RepoType};
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use tokenizers::Tokenizer;
 use tokio::sync::OnceCell;
 
+use super::model_tokenizer::ModelNotLoaded;
 use super::token_output_stream::TokenOutputStream;
+use crate::metrics::GLOBAL_METRICS;
 
 pub const MODEL_ID: &'static str = "Qwen/Qwen2-7B";
 // pub static GLOBAL_INFERENCE_MODEL: OnceCell<Arc<RwLock<ModelBase>>> = OnceCell::const_new();
@@ -149,11 +152,13 @@ impl TextGeneration {
                 .unsqueeze(0)
                 .context(format!("{}:{}", file!(), line!()))?;
 
+            let start = Instant::now();
             let logits = self.model.forward(&input, start_pos).context(format!(
                 "{}:{}",
                 file!(),
                 line!()
             ))?;
+            GLOBAL_METRICS.observe_forward("generation", start);
             let logits = logits
                 .squeeze(0)
                 .context(format!("{}:{}", file!(), line!()))?
@@ -194,15 +199,21 @@ impl TextGeneration {
             answer.push_str(rest.as_str());
         }
         self.model.clear_kv_cache();
+        GLOBAL_METRICS
+            .generation_tokens
+            .with(&[])
+            .inc_by(generated_tokens as u64);
 
         Ok(answer)
     }
 }
 
 pub fn answer(question: &str, max_len: usize) -> Result<String> {
+    // 生成模型串行执行，等待写锁的调用计入队列深度
+    let _queue = GLOBAL_METRICS.enter_model("generation");
     match GLOBAL_PIPELINE
         .get()
-        .unwrap()
+        .ok_or(ModelNotLoaded("generation model not loaded"))?
         .write()
         .unwrap()
         .run(question, max_len)
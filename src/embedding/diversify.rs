@@ -0,0 +1,126 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// 召回结果多样化方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "lowercase")]
+pub enum Diversify {
+    /// Maximal Marginal Relevance
+    Mmr {
+        // 相关性与多样性的权衡，1.0 等价于不做多样化
+        #[serde(default = "Diversify::lambda_default")]
+        lambda: f32,
+        // 参与 MMR 选择的候选数量，不小于 limit
+        #[serde(default = "Diversify::fetch_k_default")]
+        fetch_k: u64,
+    },
+}
+
+impl Diversify {
+    fn lambda_default() -> f32 {
+        0.5
+    }
+    fn fetch_k_default() -> u64 {
+        50
+    }
+
+    pub fn fetch_k(&self) -> u64 {
+        match self {
+            Diversify::Mmr { fetch_k, .. } => *fetch_k,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Diversify::Mmr { lambda, .. } if !(0.0..=1.0).contains(lambda) => Err(anyhow!(
+                "diversify.lambda must be between 0 and 1, got {}",
+                lambda
+            )),
+            Diversify::Mmr { .. } => Ok(()),
+        }
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let mut dot = 0.0;
+    let mut norm_a = 0.0;
+    let mut norm_b = 0.0;
+    for (x, y) in a.iter().zip(b.iter()) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// MMR 选择，relevance 为候选与 query 的相似度，vectors 为候选向量，
+/// 返回被选中候选的下标，按选中顺序排列
+pub fn mmr_select(relevance: &[f32], vectors: &[Vec<f32>], k: usize, lambda: f32) -> Vec<usize> {
+    let n = relevance.len().min(vectors.len());
+    let mut selected: Vec<usize> = vec![];
+    // 每个候选与已选集合的最大相似度
+    let mut max_sim = vec![f32::MIN; n];
+    let mut remaining = (0..n).collect::<Vec<usize>>();
+
+    while selected.len() < k && !remaining.is_empty() {
+        let mut best_pos = 0;
+        let mut best_score = f32::MIN;
+        for (pos, &idx) in remaining.iter().enumerate() {
+            let redundancy = match selected.is_empty() {
+                true => 0.0,
+                false => max_sim[idx],
+            };
+            let score = lambda * relevance[idx] - (1.0 - lambda) * redundancy;
+            if score > best_score {
+                best_score = score;
+                best_pos = pos;
+            }
+        }
+        let chosen = remaining.remove(best_pos);
+        selected.push(chosen);
+        for &idx in remaining.iter() {
+            let sim = cosine_similarity(&vectors[idx], &vectors[chosen]);
+            if sim > max_sim[idx] {
+                max_sim[idx] = sim;
+            }
+        }
+    }
+    selected
+}
+
+#[cfg(test)]
+mod test {
+    use super::{mmr_select, Diversify};
+
+    //cargo test embedding::diversify::test::test_mmr_select -- --nocapture
+    #[test]
+    fn test_mmr_select() {
+        // 0 与 1 几乎重复，2 相关性略低但方向不同
+        let vectors = vec![vec![1.0, 0.0], vec![0.99, 0.01], vec![0.0, 1.0]];
+        let relevance = vec![0.95, 0.94, 0.80];
+
+        let r = mmr_select(&relevance, &vectors, 2, 0.5);
+        assert_eq!(r, vec![0, 2]);
+
+        // lambda = 1 时退化为按相关性排序
+        let r = mmr_select(&relevance, &vectors, 2, 1.0);
+        assert_eq!(r, vec![0, 1]);
+    }
+
+    //cargo test embedding::diversify::test::test_validate -- --nocapture
+    #[test]
+    fn test_validate() {
+        let mmr = |lambda| Diversify::Mmr {
+            lambda,
+            fetch_k: 50,
+        };
+        assert!(mmr(0.0).validate().is_ok());
+        assert!(mmr(1.0).validate().is_ok());
+        assert!(mmr(-0.1).validate().is_err());
+        assert!(mmr(1.5).validate().is_err());
+        assert!(mmr(f32::NAN).validate().is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use super::embedding_batch;
+use crate::configure::get_config;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmbedFormat {
+    // 每行 {"line", "id", "embedding"}
+    Jsonl,
+    // numpy float32 二维数组
+    Npy,
+    // 按行连续存放的小端 f32
+    F32,
+}
+
+impl FromStr for EmbedFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "jsonl" => Ok(Self::Jsonl),
+            "npy" => Ok(Self::Npy),
+            "f32" | "bin" => Ok(Self::F32),
+            _ => Err(anyhow!("unsupported format {}", s)),
+        }
+    }
+}
+
+/// 为文件或标准输入中的每一行计算向量
+pub struct EmbedFile {
+    // 为空时读取标准输入
+    pub input: Option<String>,
+    // 输入为 jsonl，从 text_field 读取文本
+    pub jsonl: bool,
+    pub text_field: Option<String>,
+    pub format: EmbedFormat,
+    // 为空时写到标准输出
+    pub output: Option<String>,
+    pub batch_size: usize,
+}
+
+struct EmbedLine {
+    line: usize,
+    id: Option<Value>,
+    text: String,
+}
+
+impl EmbedFile {
+    /// 返回 (行数, 向量维度)
+    pub async fn execute(&self) -> Result<(usize, usize)> {
+        let ingest = get_config()?.ingest;
+        let text_field = self.text_field.clone().unwrap_or(ingest.jsonl_text_field);
+        let reader: Box<dyn BufRead> = match self.input.as_deref() {
+            None | Some("-") => Box::new(BufReader::new(io::stdin())),
+            Some(f) => Box::new(BufReader::new(File::open(f)?)),
+        };
+        let writer: Box<dyn Write> = match &self.output {
+            Some(f) => Box::new(File::create(f)?),
+            None => Box::new(io::stdout()),
+        };
+        let mut writer = BufWriter::new(writer);
+
+        let mut out = EmbedOutput {
+            format: self.format,
+            rows: 0,
+            dimension: 0,
+            npy_data: vec![],
+        };
+        let mut batch = vec![];
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            match self.jsonl {
+                true => {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let value: Value = serde_json::from_str(&line)?;
+                    let text = value
+                        .get(&text_field)
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                            anyhow!("line {} has no text field {}", idx + 1, text_field)
+                        })?
+                        .to_string();
+                    batch.push(EmbedLine {
+                        line: idx,
+                        id: value.get(&ingest.jsonl_id_field).cloned(),
+                        text,
+                    });
+                }
+                false => batch.push(EmbedLine {
+                    line: idx,
+                    id: None,
+                    text: line,
+                }),
+            }
+            if batch.len() >= self.batch_size.max(1) {
+                out.write_batch(std::mem::take(&mut batch), &mut writer)
+                    .await?;
+            }
+        }
+        if !batch.is_empty() {
+            out.write_batch(batch, &mut writer).await?;
+        }
+
+        if self.format == EmbedFormat::Npy {
+            writer.write_all(&npy_header(out.rows, out.dimension))?;
+            for v in &out.npy_data {
+                writer.write_all(&v.to_le_bytes())?;
+            }
+        }
+        writer.flush()?;
+        Ok((out.rows, out.dimension))
+    }
+}
+
+struct EmbedOutput {
+    format: EmbedFormat,
+    rows: usize,
+    dimension: usize,
+    // npy 头部需要行数，先缓存全部向量
+    npy_data: Vec<f32>,
+}
+
+impl EmbedOutput {
+    async fn write_batch(&mut self, batch: Vec<EmbedLine>, writer: &mut impl Write) -> Result<()> {
+        let texts = batch
+            .iter()
+            .map(|l| l.text.clone())
+            .collect::<Vec<String>>();
+        let embeddings = embedding_batch(&texts).await?;
+        for (l, embedding) in batch.into_iter().zip(embeddings) {
+            self.dimension = embedding.len();
+            self.rows += 1;
+            match self.format {
+                EmbedFormat::Jsonl => {
+                    let mut row = json!({"line": l.line, "embedding": embedding});
+                    if let Some(id) = l.id {
+                        row["id"] = id;
+                    }
+                    serde_json::to_writer(&mut *writer, &row)?;
+                    writer.write_all(b"\n")?;
+                }
+                EmbedFormat::Npy => self.npy_data.extend(embedding),
+                EmbedFormat::F32 => {
+                    for v in embedding {
+                        writer.write_all(&v.to_le_bytes())?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// npy 1.0 格式头，数据为 (rows, cols) 的小端 float32
+pub fn npy_header(rows: usize, cols: usize) -> Vec<u8> {
+    let mut dict = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        rows, cols
+    );
+    // magic(6) + version(2) + header_len(2) + dict 需按 64 字节对齐，以 \n 结尾
+    let unpadded = 10 + dict.len() + 1;
+    dict.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    dict.push('\n');
+
+    let mut header = b"\x93NUMPY\x01\x00".to_vec();
+    header.extend_from_slice(&(dict.len() as u16).to_le_bytes());
+    header.extend_from_slice(dict.as_bytes());
+    header
+}
+
+#[cfg(test)]
+mod test {
+    use super::npy_header;
+
+    //cargo test embedding::embed_file::test::test_npy_header -- --nocapture
+    #[test]
+    fn test_npy_header() {
+        let header = npy_header(3, 768);
+        println!("{}", String::from_utf8_lossy(&header[10..]));
+        assert_eq!(header.len() % 64, 0);
+        assert_eq!(&header[..8], b"\x93NUMPY\x01\x00");
+        assert_eq!(
+            u16::from_le_bytes([header[8], header[9]]) as usize,
+            header.len() - 10
+        );
+        assert!(String::from_utf8_lossy(&header).contains("'shape': (3, 768)"));
+        assert_eq!(header.last(), Some(&b'\n'));
+    }
+}
@@ -1,5 +1,8 @@
 pub mod answer;
+pub mod diversify;
+pub mod embed_file;
 mod model_tokenizer;
+pub mod reranker;
 pub mod retriever;
 pub mod token_output_stream;
 
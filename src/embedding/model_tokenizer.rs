@@ -1,10 +1,10 @@
-use anyhow::{Error as E, Result};
+use anyhow::{anyhow, Error as E, Result};
 use candle_core::{Device, Tensor};
 use candle_nn::VarBuilder;
 use candle_transformers::models::bert::{BertModel, Config, HiddenAct, DTYPE};
 use hf_hub::{api::tokio::Api, Repo, RepoType};
 use once_cell::sync::Lazy;
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc, time::Instant};
 use tokenizers::Tokenizer;
 use tokio::{
     runtime::{Builder, Runtime},
@@ -12,6 +12,7 @@ use tokio::{
 };
 
 use crate::configure::{config_model::ConfigModel, get_config};
+use crate::metrics::GLOBAL_METRICS;
 
 pub static GLOBAL_RUNTIME: Lazy<Arc<Runtime>> = Lazy::new(|| {
     let runtime = match init_runtime() {
@@ -23,6 +24,18 @@ pub static GLOBAL_RUNTIME: Lazy<Arc<Runtime>> = Lazy::new(|| {
 
 pub static GLOBAL_EMBEDDING_MODEL: OnceCell<Arc<(BertModel, Tokenizer)>> = OnceCell::const_new();
 
+/// 模型未加载（未启用或仍在启动中），http 接口据此返回 503
+#[derive(Debug)]
+pub struct ModelNotLoaded(pub &'static str);
+
+impl std::fmt::Display for ModelNotLoaded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ModelNotLoaded {}
+
 fn init_runtime() -> Result<Runtime> {
     let rt = Builder::new_multi_thread()
         .worker_threads(num_cpus::get())
@@ -38,19 +51,73 @@ pub async fn init_model_and_tokenizer() -> Arc<(BertModel, Tokenizer)> {
     Arc::new((m, t))
 }
 
+/// 解析配置中的设备名，如 cpu、cuda:0、metal
+pub fn parse_device(name: &str) -> Result<Device> {
+    let (kind, ordinal) = match name.split_once(':') {
+        Some((k, o)) => (k, o.parse::<usize>()?),
+        None => (name, 0),
+    };
+    let device = match kind.to_lowercase().as_str() {
+        "cpu" => Device::Cpu,
+        "cuda" => Device::new_cuda(ordinal)?,
+        "metal" => Device::new_metal(ordinal)?,
+        _ => return Err(anyhow!("unsupported device {}", name)),
+    };
+    Ok(device)
+}
+
 async fn build_model_and_tokenizer(model_config: &ConfigModel) -> Result<(BertModel, Tokenizer)> {
-    let device = Device::new_cuda(0)?;
-    let repo = Repo::with_revision(
-        model_config.model_id.clone(),
-        RepoType::Model,
-        model_config.revision.clone(),
-    );
+    let device = parse_device(&model_config.device)?;
+    let (mut config, tokenizer, vb) = load_bert_from_hub(
+        &model_config.model_id,
+        &model_config.revision,
+        model_config.use_pth,
+        &device,
+    )
+    .await?;
+    if model_config.approximate_gelu {
+        config.hidden_act = HiddenAct::GeluApproximate;
+    }
+    let model = BertModel::load(vb, &config)?;
+    Ok((model, tokenizer))
+}
+
+/// 仅下载模型的 config.json，返回向量维度
+pub async fn model_hidden_size(model_id: &str, revision: &str) -> Result<usize> {
+    let repo = Repo::with_revision(model_id.to_string(), RepoType::Model, revision.to_string());
+    let config_filename = Api::new()?.repo(repo).get("config.json").await?;
+    let config = std::fs::read_to_string(config_filename)?;
+    let config: Config = serde_json::from_str(&config)?;
+    Ok(config.hidden_size)
+}
+
+/// 从 hf-hub 下载 BERT 类模型的配置、tokenizer 及权重，
+/// 返回的 VarBuilder 可同时用于加载 BertModel 以及其上的分类头
+pub(crate) async fn load_bert_from_hub(
+    model_id: &str,
+    revision: &str,
+    use_pth: bool,
+    device: &Device,
+) -> Result<(Config, Tokenizer, VarBuilder<'static>)> {
+    let (config, tokenizer, vb) = load_from_hub(model_id, revision, use_pth, device).await?;
+    let config: Config = serde_json::from_str(&config)?;
+    Ok((config, tokenizer, vb))
+}
+
+/// 从 hf-hub 下载模型，config.json 原样返回，由调用方按 model_type 解析
+pub(crate) async fn load_from_hub(
+    model_id: &str,
+    revision: &str,
+    use_pth: bool,
+    device: &Device,
+) -> Result<(String, Tokenizer, VarBuilder<'static>)> {
+    let repo = Repo::with_revision(model_id.to_string(), RepoType::Model, revision.to_string());
     let (config_filename, tokenizer_filename, weights_filename) = {
         let api = Api::new()?;
         let api = api.repo(repo);
         let config = api.get("config.json").await?;
         let tokenizer = api.get("tokenizer.json").await?;
-        let weights = if model_config.use_pth {
+        let weights = if use_pth {
             api.get("pytorch_model.bin").await?
         } else {
             api.get("model.safetensors").await?
@@ -58,32 +125,36 @@ async fn build_model_and_tokenizer(model_config: &ConfigModel) -> Result<(BertMo
         (config, tokenizer, weights)
     };
     let config = std::fs::read_to_string(config_filename)?;
-    let mut config: Config = serde_json::from_str(&config)?;
     let tokenizer = Tokenizer::from_file(tokenizer_filename).map_err(E::msg)?;
 
-    let vb = if model_config.use_pth {
-        VarBuilder::from_pth(&weights_filename, DTYPE, &device)?
+    let vb = if use_pth {
+        VarBuilder::from_pth(&weights_filename, DTYPE, device)?
     } else {
-        unsafe { VarBuilder::from_mmaped_safetensors(&[weights_filename], DTYPE, &device)? }
+        unsafe { VarBuilder::from_mmaped_safetensors(&[weights_filename], DTYPE, device)? }
     };
-    if model_config.approximate_gelu {
-        config.hidden_act = HiddenAct::GeluApproximate;
-    }
-    let model = BertModel::load(vb, &config)?;
-    Ok((model, tokenizer))
+    Ok((config, tokenizer, vb))
 }
 
 pub async fn embedding_setence(content: &str) -> Result<Vec<Vec<f32>>> {
-    let m_t = GLOBAL_EMBEDDING_MODEL.get().unwrap();
+    let m_t = GLOBAL_EMBEDDING_MODEL
+        .get()
+        .ok_or(ModelNotLoaded("embedding model not loaded"))?;
+    let _queue = GLOBAL_METRICS.enter_model("embedding");
     let tokens = m_t
         .1
         .encode(content, true)
         .map_err(E::msg)?
         .get_ids()
         .to_vec();
+    GLOBAL_METRICS
+        .embedding_tokens
+        .with(&[])
+        .inc_by(tokens.len() as u64);
     let token_ids = Tensor::new(&tokens[..], &m_t.0.device)?.unsqueeze(0)?;
     let token_type_ids = token_ids.zeros_like()?;
-    let sequence_output = m_t.0.forward(&token_ids, &token_type_ids)?;
+    let start = Instant::now();
+    let sequence_output = m_t.0.forward(&token_ids, &token_type_ids, None)?;
+    GLOBAL_METRICS.observe_forward("embedding", start);
     let (_n_sentence, n_tokens, _hidden_size) = sequence_output.dims3()?;
     let embeddings = (sequence_output.sum(1)? / (n_tokens as f64))?;
     let embeddings = normalize_l2(&embeddings)?;
@@ -91,6 +162,52 @@ pub async fn embedding_setence(content: &str) -> Result<Vec<Vec<f32>>> {
     Ok(encodings)
 }
 
+/// 批量计算向量。
+/// 模型前向不带 attention mask，为避免 padding 影响结果，按 token 长度分组，同长度的文本合并为一次前向
+pub async fn embedding_batch(contents: &[String]) -> Result<Vec<Vec<f32>>> {
+    let m_t = GLOBAL_EMBEDDING_MODEL
+        .get()
+        .ok_or(ModelNotLoaded("embedding model not loaded"))?;
+    let _queue = GLOBAL_METRICS.enter_model("embedding");
+    GLOBAL_METRICS
+        .embedding_batch_size
+        .with(&[])
+        .observe(contents.len() as f64);
+    let mut groups: BTreeMap<usize, Vec<(usize, Vec<u32>)>> = BTreeMap::new();
+    for (idx, content) in contents.iter().enumerate() {
+        let tokens = m_t
+            .1
+            .encode(content.as_str(), true)
+            .map_err(E::msg)?
+            .get_ids()
+            .to_vec();
+        GLOBAL_METRICS
+            .embedding_tokens
+            .with(&[])
+            .inc_by(tokens.len() as u64);
+        groups.entry(tokens.len()).or_default().push((idx, tokens));
+    }
+
+    let mut encodings = vec![vec![]; contents.len()];
+    for (n_tokens, rows) in groups {
+        let ids = rows
+            .iter()
+            .map(|(_, tokens)| Tensor::new(&tokens[..], &m_t.0.device))
+            .collect::<candle_core::Result<Vec<Tensor>>>()?;
+        let token_ids = Tensor::stack(&ids, 0)?;
+        let token_type_ids = token_ids.zeros_like()?;
+        let start = Instant::now();
+        let sequence_output = m_t.0.forward(&token_ids, &token_type_ids, None)?;
+        GLOBAL_METRICS.observe_forward("embedding", start);
+        let embeddings = (sequence_output.sum(1)? / (n_tokens as f64))?;
+        let embeddings = normalize_l2(&embeddings)?.to_vec2::<f32>()?;
+        for ((idx, _), embedding) in rows.into_iter().zip(embeddings) {
+            encodings[idx] = embedding;
+        }
+    }
+    Ok(encodings)
+}
+
 pub fn normalize_l2(v: &Tensor) -> Result<Tensor> {
     Ok(v.broadcast_div(&v.sqr()?.sum_keepdim(1)?.sqrt()?)?)
 }
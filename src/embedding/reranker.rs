@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Error as E, Result};
+use candle_core::{IndexOp, Tensor};
+use candle_nn::{linear, Linear, Module, VarBuilder};
+use candle_transformers::models::bert::{self, BertModel};
+use candle_transformers::models::xlm_roberta::{self, XLMRobertaForSequenceClassification};
+use std::sync::Arc;
+use std::time::Instant;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+use tokio::sync::OnceCell;
+
+use super::model_tokenizer::{load_from_hub, parse_device, ModelNotLoaded};
+use crate::configure::{config_reranker::ConfigReranker, get_config};
+use crate::metrics::GLOBAL_METRICS;
+
+pub static GLOBAL_RERANKER: OnceCell<Arc<CrossEncoder>> = OnceCell::const_new();
+
+// 单次前向的 (query, text) 对数量上限
+const RERANK_BATCH_SIZE: usize = 16;
+
+/// 交叉编码器，按 config.json 的 model_type 选择实现：
+/// xlm-roberta（bge-reranker）的位置编码从 padding_idx + 1 开始，不能用 BertModel 加载
+enum CrossEncoderModel {
+    // BertForSequenceClassification：bert.pooler.dense -> tanh -> classifier
+    Bert {
+        model: BertModel,
+        pooler: Linear,
+        classifier: Linear,
+    },
+    XlmRoberta(XLMRobertaForSequenceClassification),
+}
+
+impl CrossEncoderModel {
+    fn load(config: &str, vb: VarBuilder) -> Result<Self> {
+        let model_type = serde_json::from_str::<serde_json::Value>(config)?
+            .get("model_type")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        if let Some("xlm-roberta") = model_type.as_deref() {
+            let config: xlm_roberta::Config = serde_json::from_str(config)?;
+            let model = XLMRobertaForSequenceClassification::new(1, &config, vb)?;
+            return Ok(Self::XlmRoberta(model));
+        }
+        let config: bert::Config = serde_json::from_str(config)?;
+        let prefix = model_type.as_deref().unwrap_or("bert");
+        let model = BertModel::load(vb.clone(), &config)?;
+        let pooler = linear(
+            config.hidden_size,
+            config.hidden_size,
+            vb.pp(format!("{prefix}.pooler.dense")),
+        )?;
+        let classifier = linear(config.hidden_size, 1, vb.pp("classifier"))?;
+        Ok(Self::Bert {
+            model,
+            pooler,
+            classifier,
+        })
+    }
+
+    // 返回 (batch, 1) 的 logits
+    fn forward(
+        &self,
+        token_ids: &Tensor,
+        token_type_ids: &Tensor,
+        attention_mask: &Tensor,
+    ) -> Result<Tensor> {
+        let logits = match self {
+            Self::Bert {
+                model,
+                pooler,
+                classifier,
+            } => {
+                let sequence_output =
+                    model.forward(token_ids, token_type_ids, Some(attention_mask))?;
+                let cls = sequence_output.i((.., 0))?;
+                classifier.forward(&pooler.forward(&cls)?.tanh()?)?
+            }
+            Self::XlmRoberta(model) => model.forward(token_ids, attention_mask, token_type_ids)?,
+        };
+        Ok(logits)
+    }
+}
+
+pub struct CrossEncoder {
+    model: CrossEncoderModel,
+    tokenizer: Tokenizer,
+    device: candle_core::Device,
+}
+
+impl CrossEncoder {
+    /// 计算 query 与每条文本的相关性分数（sigmoid 归一化到 0~1），按批次补齐并带 attention mask 前向
+    pub fn score_batch(&self, query: &str, texts: &[String]) -> Result<Vec<f32>> {
+        let _queue = GLOBAL_METRICS.enter_model("reranker");
+        let mut scores = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(RERANK_BATCH_SIZE) {
+            let pairs = batch
+                .iter()
+                .map(|text| (query, text.as_str()))
+                .collect::<Vec<(&str, &str)>>();
+            let encodings = self.tokenizer.encode_batch(pairs, true).map_err(E::msg)?;
+            let mut ids = vec![];
+            let mut type_ids = vec![];
+            let mut masks = vec![];
+            for encoding in encodings.iter() {
+                ids.push(Tensor::new(encoding.get_ids(), &self.device)?);
+                type_ids.push(Tensor::new(encoding.get_type_ids(), &self.device)?);
+                masks.push(Tensor::new(encoding.get_attention_mask(), &self.device)?);
+            }
+            let token_ids = Tensor::stack(&ids, 0)?;
+            let token_type_ids = Tensor::stack(&type_ids, 0)?;
+            let attention_mask = Tensor::stack(&masks, 0)?;
+            let start = Instant::now();
+            let logits = self
+                .model
+                .forward(&token_ids, &token_type_ids, &attention_mask)?;
+            GLOBAL_METRICS.observe_forward("reranker", start);
+            let batch_scores = candle_nn::ops::sigmoid(&logits)?
+                .flatten_all()?
+                .to_vec1::<f32>()?;
+            if batch_scores.len() != batch.len() {
+                return Err(anyhow!(
+                    "reranker output {} scores for {} texts",
+                    batch_scores.len(),
+                    batch.len()
+                ));
+            }
+            scores.extend(batch_scores);
+        }
+        Ok(scores)
+    }
+}
+
+pub async fn init_global_reranker() -> Arc<CrossEncoder> {
+    let config = get_config().unwrap();
+    let reranker = build_cross_encoder(&config.reranker).await.unwrap();
+    Arc::new(reranker)
+}
+
+async fn build_cross_encoder(reranker_config: &ConfigReranker) -> Result<CrossEncoder> {
+    let device = parse_device(&get_config()?.model.device)?;
+    let (config, mut tokenizer, vb) = load_from_hub(
+        &reranker_config.model_id,
+        &reranker_config.revision,
+        reranker_config.use_pth,
+        &device,
+    )
+    .await?;
+    tokenizer
+        .with_truncation(Some(TruncationParams {
+            max_length: reranker_config.max_length,
+            ..Default::default()
+        }))
+        .map_err(E::msg)?;
+    // 同一批次补齐到最长文本，padding 位置由 attention mask 屏蔽
+    let pad_id = serde_json::from_str::<serde_json::Value>(&config)?
+        .get("pad_token_id")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    let pad_token = tokenizer
+        .id_to_token(pad_id)
+        .ok_or_else(|| anyhow!("pad token {} not in tokenizer", pad_id))?;
+    tokenizer.with_padding(Some(PaddingParams {
+        strategy: PaddingStrategy::BatchLongest,
+        pad_id,
+        pad_token,
+        ..Default::default()
+    }));
+    let model = CrossEncoderModel::load(&config, vb)?;
+    Ok(CrossEncoder {
+        model,
+        tokenizer,
+        device,
+    })
+}
+
+/// 对 texts 按与 query 的相关性重新打分，返回 (原始下标, 分数)，分数降序。
+/// 前向计算在阻塞线程池中执行，不占用异步运行时的工作线程
+pub async fn rerank(query: &str, texts: &[String]) -> Result<Vec<(usize, f32)>> {
+    let cross_encoder = GLOBAL_RERANKER.get().cloned().ok_or(ModelNotLoaded(
+        "reranker not loaded, set reranker.enable in config",
+    ))?;
+    let query = query.to_string();
+    let texts = texts.to_vec();
+    let scores =
+        tokio::task::spawn_blocking(move || cross_encoder.score_batch(&query, &texts)).await??;
+    let mut scored = scores
+        .into_iter()
+        .enumerate()
+        .collect::<Vec<(usize, f32)>>();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    Ok(scored)
+}
@@ -1,13 +1,168 @@
-use super::embedding_setence;
+use super::{
+    diversify::{mmr_select, Diversify},
+    embedding_setence,
+    reranker::rerank,
+};
 use crate::{configure::get_config, resources::resource_qdrant::search_points};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use qdrant_client::qdrant::SearchResponse;
+use qdrant_client::qdrant::{vector_output::Vector, Condition, Filter, ScoredPoint};
+use serde::Deserialize;
+use std::collections::HashMap;
 
-pub async fn retriever(content: &str, limit: u64) -> Result<SearchResponse> {
-    let collection_name = get_config()?.qdrant.collection;
+/// 检索的可选处理阶段
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RetrieverOptions {
+    // 是否对召回结果做交叉编码器重排，缺省时以配置文件 reranker.enable 为准
+    #[serde(default)]
+    pub rerank: Option<bool>,
+    // 召回结果多样化，如 {"method": "mmr", "lambda": 0.5, "fetch_k": 50}
+    #[serde(default)]
+    pub diversify: Option<Diversify>,
+    // payload 精确匹配过滤，如 {"path": "docs/a.md", "chunk_index": 0}
+    #[serde(default)]
+    pub filter: Option<HashMap<String, serde_json::Value>>,
+    // 向量相似度下限，低于该值的候选不返回
+    #[serde(default)]
+    pub score_threshold: Option<f32>,
+}
+
+impl RetrieverOptions {
+    /// 检查 filter 及 diversify 参数
+    pub fn validate(&self) -> Result<()> {
+        self.qdrant_filter()?;
+        if let Some(d) = &self.diversify {
+            d.validate()?;
+        }
+        Ok(())
+    }
+
+    /// 将 filter 转换为 qdrant 的 must 条件，仅支持字符串、整数及布尔值
+    pub fn qdrant_filter(&self) -> Result<Option<Filter>> {
+        let filter = match &self.filter {
+            Some(f) if !f.is_empty() => f,
+            _ => return Ok(None),
+        };
+        let mut conditions = vec![];
+        for (key, value) in filter {
+            let condition = match value {
+                serde_json::Value::String(s) => Condition::matches(key, s.clone()),
+                serde_json::Value::Bool(b) => Condition::matches(key, *b),
+                serde_json::Value::Number(n) => match n.as_i64() {
+                    Some(i) => Condition::matches(key, i),
+                    None => return Err(anyhow!("filter {} must be an integer", key)),
+                },
+                _ => return Err(anyhow!("unsupported filter value {} for {}", value, key)),
+            };
+            conditions.push(condition);
+        }
+        Ok(Some(Filter::must(conditions)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetrievedPoint {
+    pub point: ScoredPoint,
+    pub rerank_score: Option<f32>,
+}
+
+pub async fn retriever(
+    content: &str,
+    limit: u64,
+    options: &RetrieverOptions,
+) -> Result<Vec<RetrievedPoint>> {
+    options.validate()?;
+    let config = get_config()?;
+    let rerank_enable = options.rerank.unwrap_or(config.reranker.enable);
     let embedding = embedding_setence(content).await?;
     let vector = embedding[0].clone();
-    let r = search_points(collection_name, vector, limit).await?;
-    Ok(r)
+
+    // 重排时多召回一些候选，重排后再截断到 limit
+    let keep = match rerank_enable {
+        true => limit.max(config.reranker.top_n),
+        false => limit,
+    };
+    let fetch = match &options.diversify {
+        Some(d) => keep.max(d.fetch_k()),
+        None => keep,
+    };
+    let r = search_points(
+        config.qdrant.collection,
+        vector,
+        fetch,
+        options.diversify.is_some(),
+        options.qdrant_filter()?,
+        options.score_threshold,
+    )
+    .await?;
+    let mut points = r
+        .result
+        .into_iter()
+        .map(|point| RetrievedPoint {
+            point,
+            rerank_score: None,
+        })
+        .collect::<Vec<RetrievedPoint>>();
+
+    if let Some(d) = &options.diversify {
+        points = diversify_points(points, d, keep as usize);
+    }
+
+    if rerank_enable {
+        points = rerank_points(content, points, &config.reranker.text_field).await?;
+    }
+    points.truncate(limit as usize);
+    Ok(points)
+}
+
+fn diversify_points(
+    points: Vec<RetrievedPoint>,
+    diversify: &Diversify,
+    k: usize,
+) -> Vec<RetrievedPoint> {
+    match diversify {
+        Diversify::Mmr { lambda, .. } => {
+            let relevance = points.iter().map(|p| p.point.score).collect::<Vec<f32>>();
+            let vectors = points
+                .iter()
+                .map(|p| point_dense_vector(&p.point))
+                .collect::<Vec<Vec<f32>>>();
+            mmr_select(&relevance, &vectors, k, *lambda)
+                .into_iter()
+                .map(|idx| points[idx].clone())
+                .collect()
+        }
+    }
+}
+
+pub(crate) fn point_dense_vector(point: &ScoredPoint) -> Vec<f32> {
+    match point.vectors.as_ref().and_then(|v| v.get_vector()) {
+        Some(Vector::Dense(d)) => d.data,
+        _ => vec![],
+    }
+}
+
+async fn rerank_points(
+    query: &str,
+    points: Vec<RetrievedPoint>,
+    text_field: &str,
+) -> Result<Vec<RetrievedPoint>> {
+    let texts = points
+        .iter()
+        .map(|p| match p.point.payload.get(text_field) {
+            Some(v) => match v.as_str() {
+                Some(s) => s.clone(),
+                None => v.to_string(),
+            },
+            None => "".to_string(),
+        })
+        .collect::<Vec<String>>();
+    let scored = rerank(query, &texts).await?;
+    let mut reranked = vec![];
+    for (idx, score) in scored {
+        let mut p = points[idx].clone();
+        p.rerank_score = Some(score);
+        reranked.push(p);
+    }
+    Ok(reranked)
 }
@@ -0,0 +1,149 @@
+use super::metrics::{percentiles, Percentiles};
+use crate::embedding::{embedding_batch, GLOBAL_EMBEDDING_MODEL};
+use anyhow::{anyhow, Error as E, Result};
+use candle_transformers::models::bert::DTYPE;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+const WORDS: [&str; 24] = [
+    "vector",
+    "search",
+    "embedding",
+    "model",
+    "server",
+    "query",
+    "document",
+    "token",
+    "batch",
+    "latency",
+    "index",
+    "payload",
+    "collection",
+    "retrieval",
+    "semantic",
+    "rust",
+    "candle",
+    "qdrant",
+    "chunk",
+    "score",
+    "rerank",
+    "device",
+    "memory",
+    "throughput",
+];
+
+/// 基准测试参数，对 lengths × batch_sizes × concurrency 的每种组合分别测量
+pub struct BenchEmbedding {
+    // 合成输入的单词数
+    pub lengths: Vec<usize>,
+    pub batch_sizes: Vec<usize>,
+    pub concurrency: Vec<usize>,
+    // 每个并发 worker 的调用次数
+    pub iterations: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub length: usize,
+    // 实际 token 数（含特殊 token）
+    pub tokens_per_input: usize,
+    pub batch_size: usize,
+    pub concurrency: usize,
+    pub calls: usize,
+    pub embeddings_per_sec: f64,
+    pub tokens_per_sec: f64,
+    // 单次 embedding_batch 调用的延迟
+    pub latency_ms: Percentiles,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub model_id: String,
+    pub device: String,
+    pub dtype: String,
+    pub results: Vec<BenchResult>,
+}
+
+impl BenchEmbedding {
+    pub async fn execute(&self, model_id: &str) -> Result<BenchReport> {
+        let m_t = GLOBAL_EMBEDDING_MODEL
+            .get()
+            .ok_or_else(|| anyhow!("embedding model not loaded"))?;
+        let device = format!("{:?}", m_t.0.device);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut results = vec![];
+        for length in &self.lengths {
+            let text = synthetic_text(&mut rng, *length);
+            let tokens_per_input = m_t.1.encode(text.as_str(), true).map_err(E::msg)?.len();
+            for batch_size in &self.batch_sizes {
+                let batch = vec![text.clone(); *batch_size];
+                // 预热，排除首次分配及 kernel 初始化
+                embedding_batch(&batch).await?;
+                for concurrency in &self.concurrency {
+                    let r = self
+                        .run(*length, batch.clone(), *concurrency, tokens_per_input)
+                        .await?;
+                    log::info!("{:?}", r);
+                    results.push(r);
+                }
+            }
+        }
+        Ok(BenchReport {
+            model_id: model_id.to_string(),
+            device,
+            dtype: format!("{:?}", DTYPE),
+            results,
+        })
+    }
+
+    async fn run(
+        &self,
+        length: usize,
+        batch: Vec<String>,
+        concurrency: usize,
+        tokens_per_input: usize,
+    ) -> Result<BenchResult> {
+        let batch_size = batch.len();
+        let iterations = self.iterations.max(1);
+        let start = Instant::now();
+        let mut workers = vec![];
+        for _ in 0..concurrency.max(1) {
+            let batch = batch.clone();
+            workers.push(tokio::spawn(async move {
+                let mut latencies = vec![];
+                for _ in 0..iterations {
+                    let now = Instant::now();
+                    embedding_batch(&batch).await?;
+                    latencies.push(now.elapsed().as_secs_f64() * 1000.0);
+                }
+                Ok::<Vec<f64>, anyhow::Error>(latencies)
+            }));
+        }
+        let mut latencies = vec![];
+        for w in workers {
+            latencies.extend(w.await??);
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+
+        let embeddings = (latencies.len() * batch_size) as f64;
+        Ok(BenchResult {
+            length,
+            tokens_per_input,
+            batch_size,
+            concurrency: concurrency.max(1),
+            calls: latencies.len(),
+            embeddings_per_sec: embeddings / elapsed,
+            tokens_per_sec: embeddings * tokens_per_input as f64 / elapsed,
+            latency_ms: percentiles(&latencies),
+        })
+    }
+}
+
+fn synthetic_text(rng: &mut StdRng, words: usize) -> String {
+    (0..words.max(1))
+        .map(|_| WORDS[rng.gen_range(0..WORDS.len())])
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
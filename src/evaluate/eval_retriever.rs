@@ -0,0 +1,159 @@
+use super::metrics::{ndcg_at_k, percentiles, recall_at_k, reciprocal_rank, Percentiles};
+use crate::{
+    commons::read_lines,
+    configure::get_config,
+    embedding::retriever::{retriever, RetrievedPoint, RetrieverOptions},
+    resources::resource_qdrant::point_id_to_string,
+};
+use anyhow::{anyhow, Result};
+use qdrant_client::Payload;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, time::Instant};
+
+/// 评测集中的一条查询，relevant_ids 为相关文档的 point id 或 id_field 的取值
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvalQuery {
+    pub query: String,
+    pub relevant_ids: Vec<String>,
+}
+
+pub struct EvalRetriever {
+    pub file: String,
+    pub k: u64,
+    // 以 payload 中的字段作为文档 id，如 path；为空时使用 point id
+    pub id_field: Option<String>,
+    pub options: RetrieverOptions,
+}
+
+/// 评测时的模型及检索配置，用于比较不同配置的报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalSetup {
+    pub model_id: String,
+    pub revision: String,
+    pub collection: String,
+    pub splitter: serde_json::Value,
+    pub rerank: bool,
+    pub reranker_model_id: String,
+    pub diversify: Option<serde_json::Value>,
+    pub id_field: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub query: String,
+    pub recall: f64,
+    pub reciprocal_rank: f64,
+    pub ndcg: f64,
+    pub latency_ms: f64,
+    pub retrieved: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub setup: EvalSetup,
+    pub k: u64,
+    pub queries: usize,
+    pub recall_at_k: f64,
+    pub mrr: f64,
+    pub ndcg_at_k: f64,
+    pub latency_ms: Percentiles,
+    pub per_query: Vec<QueryResult>,
+}
+
+impl EvalRetriever {
+    pub async fn execute(&self) -> Result<EvalReport> {
+        let config = get_config()?;
+        let queries = self.read_queries()?;
+        if queries.is_empty() {
+            return Err(anyhow!("no query in {}", self.file));
+        }
+        let k = self.k as usize;
+
+        // 预热一次，首个请求的初始化开销不计入延迟
+        retriever(&queries[0].query, self.k, &self.options).await?;
+
+        let mut per_query = vec![];
+        for q in queries {
+            let now = Instant::now();
+            let points = retriever(&q.query, self.k, &self.options).await?;
+            let latency_ms = now.elapsed().as_secs_f64() * 1000.0;
+            let retrieved = self.document_ids(&points);
+            let relevant = q.relevant_ids.into_iter().collect::<HashSet<String>>();
+            per_query.push(QueryResult {
+                recall: recall_at_k(&retrieved, &relevant, k),
+                reciprocal_rank: reciprocal_rank(&retrieved, &relevant, k),
+                ndcg: ndcg_at_k(&retrieved, &relevant, k),
+                query: q.query,
+                latency_ms,
+                retrieved,
+            });
+        }
+
+        let n = per_query.len() as f64;
+        let latencies = per_query.iter().map(|r| r.latency_ms).collect::<Vec<f64>>();
+        let report = EvalReport {
+            setup: EvalSetup {
+                model_id: config.model.model_id.clone(),
+                revision: config.model.revision.clone(),
+                collection: config.qdrant.collection.clone(),
+                splitter: serde_json::to_value(&config.ingest.splitter)?,
+                rerank: self.options.rerank.unwrap_or(config.reranker.enable),
+                reranker_model_id: config.reranker.model_id.clone(),
+                diversify: match &self.options.diversify {
+                    Some(d) => Some(serde_json::to_value(d)?),
+                    None => None,
+                },
+                id_field: self.id_field.clone(),
+            },
+            k: self.k,
+            queries: per_query.len(),
+            recall_at_k: per_query.iter().map(|r| r.recall).sum::<f64>() / n,
+            mrr: per_query.iter().map(|r| r.reciprocal_rank).sum::<f64>() / n,
+            ndcg_at_k: per_query.iter().map(|r| r.ndcg).sum::<f64>() / n,
+            latency_ms: percentiles(&latencies),
+            per_query,
+        };
+        Ok(report)
+    }
+
+    fn read_queries(&self) -> Result<Vec<EvalQuery>> {
+        let mut queries = vec![];
+        for (idx, line) in read_lines(&self.file)?.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let q = serde_json::from_str::<EvalQuery>(&line)
+                .map_err(|e| anyhow!("{}:{} {}", self.file, idx + 1, e))?;
+            queries.push(q);
+        }
+        Ok(queries)
+    }
+
+    // 按 id_field 取文档 id 时，同一文档的多个 chunk 只保留排名最高的一个
+    fn document_ids(&self, points: &[RetrievedPoint]) -> Vec<String> {
+        let field = match &self.id_field {
+            Some(f) => f,
+            None => {
+                return points
+                    .iter()
+                    .map(|p| point_id_to_string(&p.point.id))
+                    .collect()
+            }
+        };
+        let mut seen = HashSet::new();
+        let mut ids = vec![];
+        for p in points {
+            let payload = serde_json::Value::from(Payload::from(p.point.payload.clone()));
+            let id = match payload.get(field) {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(v) => v.to_string(),
+                None => continue,
+            };
+            if seen.insert(id.clone()) {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 前 k 个结果中召回的相关文档占全部相关文档的比例
+pub fn recall_at_k(retrieved: &[String], relevant: &HashSet<String>, k: usize) -> f64 {
+    if relevant.is_empty() {
+        return 0.0;
+    }
+    let hits = retrieved
+        .iter()
+        .take(k)
+        .filter(|id| relevant.contains(*id))
+        .count();
+    hits as f64 / relevant.len() as f64
+}
+
+/// 第一个相关结果排名的倒数，前 k 个中没有相关结果时为 0
+pub fn reciprocal_rank(retrieved: &[String], relevant: &HashSet<String>, k: usize) -> f64 {
+    retrieved
+        .iter()
+        .take(k)
+        .position(|id| relevant.contains(id))
+        .map_or(0.0, |idx| 1.0 / (idx + 1) as f64)
+}
+
+/// 二值相关性下的 nDCG@k
+pub fn ndcg_at_k(retrieved: &[String], relevant: &HashSet<String>, k: usize) -> f64 {
+    let dcg = retrieved
+        .iter()
+        .take(k)
+        .enumerate()
+        .filter(|(_, id)| relevant.contains(*id))
+        .map(|(idx, _)| 1.0 / ((idx + 2) as f64).log2())
+        .sum::<f64>();
+    let idcg = (0..relevant.len().min(k))
+        .map(|idx| 1.0 / ((idx + 2) as f64).log2())
+        .sum::<f64>();
+    match idcg > 0.0 {
+        true => dcg / idcg,
+        false => 0.0,
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// 最近秩法计算分位数
+pub fn percentiles(values: &[f64]) -> Percentiles {
+    if values.is_empty() {
+        return Percentiles::default();
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let at = |p: f64| {
+        let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    };
+    Percentiles {
+        mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p50: at(50.0),
+        p90: at(90.0),
+        p95: at(95.0),
+        p99: at(99.0),
+        max: sorted[sorted.len() - 1],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ndcg_at_k, percentiles, recall_at_k, reciprocal_rank};
+    use std::collections::HashSet;
+
+    //cargo test evaluate::metrics::test::test_metrics -- --nocapture
+    #[test]
+    fn test_metrics() {
+        let retrieved = ["a", "b", "c", "d"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>();
+        let relevant = ["b", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<HashSet<String>>();
+
+        assert!((recall_at_k(&retrieved, &relevant, 2) - 1.0 / 3.0).abs() < 1e-9);
+        assert!((recall_at_k(&retrieved, &relevant, 4) - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(reciprocal_rank(&retrieved, &relevant, 4), 0.5);
+        assert_eq!(reciprocal_rank(&retrieved, &relevant, 1), 0.0);
+
+        let dcg = 1.0 / 3f64.log2() + 1.0 / 5f64.log2();
+        let idcg = 1.0 + 1.0 / 3f64.log2() + 1.0 / 4f64.log2();
+        let ndcg = ndcg_at_k(&retrieved, &relevant, 4);
+        println!("ndcg@4 {}", ndcg);
+        assert!((ndcg - dcg / idcg).abs() < 1e-9);
+    }
+
+    //cargo test evaluate::metrics::test::test_percentiles -- --nocapture
+    #[test]
+    fn test_percentiles() {
+        let values = (1..=100).map(|v| v as f64).collect::<Vec<f64>>();
+        let p = percentiles(&values);
+        println!("{:?}", p);
+        assert_eq!(p.p50, 50.0);
+        assert_eq!(p.p95, 95.0);
+        assert_eq!(p.p99, 99.0);
+        assert_eq!(p.max, 100.0);
+        assert_eq!(p.mean, 50.5);
+    }
+}
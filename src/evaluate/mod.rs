@@ -0,0 +1,6 @@
+mod bench_embedding;
+mod eval_retriever;
+mod metrics;
+
+pub use bench_embedding::*;
+pub use eval_retriever::*;
@@ -1,8 +1,11 @@
 //! 自定义错误
 use std::fmt::Display;
 
-use axum::{response::IntoResponse, Json};
+use axum::{http::StatusCode, response::IntoResponse, Json};
+use qdrant_client::QdrantError;
 
+use crate::embedding::ModelNotLoaded;
+use crate::httpserver::middleware::current_request_id;
 use crate::httpserver::module::Response;
 
 /// Error type
@@ -15,6 +18,24 @@ pub enum AppErrorType {
     DbError,
     /// 未找到
     NotFound,
+    /// 请求参数错误
+    Validation,
+    /// 模型未加载或推理失败
+    Model,
+    /// 向量库错误
+    VectorStore,
+    /// 超过并发上限
+    Overloaded,
+    /// 处理超时
+    Timeout,
+    /// 未认证
+    Unauthorized,
+    /// 无权限
+    Forbidden,
+    /// 模型未加载或服务启动中
+    Unavailable,
+    /// 请求体超过大小上限
+    PayloadTooLarge,
 }
 
 /// 应用错误
@@ -34,9 +55,34 @@ impl AppError {
         match self.error_type {
             AppErrorType::DbError => 1,
             AppErrorType::NotFound => 2,
+            AppErrorType::Validation => 3,
+            AppErrorType::Model => 4,
+            AppErrorType::VectorStore => 5,
+            AppErrorType::Overloaded => 6,
+            AppErrorType::Timeout => 7,
+            AppErrorType::Unauthorized => 8,
+            AppErrorType::Forbidden => 9,
+            AppErrorType::Unavailable => 10,
+            AppErrorType::PayloadTooLarge => 11,
             AppErrorType::UnknowErr => 9999,
         }
     }
+    /// HTTP 状态码
+    fn status(&self) -> StatusCode {
+        match self.error_type {
+            AppErrorType::Validation => StatusCode::BAD_REQUEST,
+            AppErrorType::NotFound => StatusCode::NOT_FOUND,
+            AppErrorType::VectorStore => StatusCode::BAD_GATEWAY,
+            AppErrorType::Overloaded | AppErrorType::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            AppErrorType::Timeout => StatusCode::REQUEST_TIMEOUT,
+            AppErrorType::Unauthorized => StatusCode::UNAUTHORIZED,
+            AppErrorType::Forbidden => StatusCode::FORBIDDEN,
+            AppErrorType::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            AppErrorType::Model | AppErrorType::DbError | AppErrorType::UnknowErr => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
     /// 从上级错误中创建应用错误
     #[allow(dead_code)]
     fn from_err(err: impl ToString, error_type: AppErrorType) -> Self {
@@ -55,6 +101,44 @@ impl AppError {
             error_type,
         }
     }
+    /// 以错误信息创建应用错误
+    pub fn new(err: impl ToString, error_type: AppErrorType) -> Self {
+        Self {
+            message: Some(err.to_string()),
+            cause: None,
+            error_type,
+        }
+    }
+    /// 请求参数错误
+    pub fn validation(err: impl ToString) -> Self {
+        Self::new(err, AppErrorType::Validation)
+    }
+    /// 按错误链中的底层错误分类，无法识别时使用 default
+    pub fn classify(err: anyhow::Error, default: AppErrorType) -> Self {
+        let mut error_type = default;
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<QdrantError>() {
+                error_type = match e {
+                    QdrantError::ResourceExhaustedError { .. } => AppErrorType::Overloaded,
+                    _ => AppErrorType::VectorStore,
+                };
+                break;
+            }
+            if cause.is::<ModelNotLoaded>() {
+                error_type = AppErrorType::Unavailable;
+                break;
+            }
+            if cause.is::<candle_core::Error>() {
+                error_type = AppErrorType::Model;
+                break;
+            }
+            if cause.is::<tokio::time::error::Elapsed>() {
+                error_type = AppErrorType::Timeout;
+                break;
+            }
+        }
+        Self::new(err, error_type)
+    }
     /// 数据库错误
     #[allow(dead_code)]
     pub fn db_error(err: impl ToString) -> Self {
@@ -78,11 +162,43 @@ impl Display for AppError {
 impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
         let code = (&self).code();
+        let status = self.status();
         let msg = match self.message {
             Some(msg) => msg,
             None => "有错误发生".to_string(),
         };
-        let res: Response<()> = Response::err(code, msg);
-        Json(res).into_response()
+        let res: Response<()> = Response::err(code, msg).with_request_id(current_request_id());
+        (status, Json(res)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AppError, AppErrorType};
+    use crate::embedding::ModelNotLoaded;
+    use axum::http::StatusCode;
+    use qdrant_client::QdrantError;
+
+    //cargo test httpserver::exception::error::test::test_classify -- --nocapture
+    #[test]
+    fn test_classify() {
+        let e = anyhow::Error::from(QdrantError::ConversionError("x".to_string()))
+            .context("search points");
+        let err = AppError::classify(e, AppErrorType::UnknowErr);
+        println!("{:?}", err);
+        assert!(matches!(err.error_type, AppErrorType::VectorStore));
+        assert_eq!(err.status(), StatusCode::BAD_GATEWAY);
+
+        let e = anyhow::Error::from(candle_core::Error::Msg("oom".to_string()));
+        let err = AppError::classify(e, AppErrorType::UnknowErr);
+        assert!(matches!(err.error_type, AppErrorType::Model));
+
+        let e = anyhow::Error::new(ModelNotLoaded("reranker not loaded")).context("rerank");
+        let err = AppError::classify(e, AppErrorType::Model);
+        assert_eq!(err.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let err = AppError::classify(anyhow::anyhow!("bad"), AppErrorType::Validation);
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.code(), 3);
     }
 }
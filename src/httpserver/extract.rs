@@ -0,0 +1,39 @@
+//! 请求体提取器
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    http::StatusCode,
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::httpserver::exception::{AppError, AppErrorType};
+
+/// 与 axum::Json 相同，解析失败时返回统一的 json 错误响应（Validation）而不是纯文本，
+/// 请求体超过 DefaultBodyLimit 时返回 413
+pub struct ReqJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ReqJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(Self(value)),
+            Err(rejection) => Err(json_rejection(rejection)),
+        }
+    }
+}
+
+fn json_rejection(rejection: JsonRejection) -> AppError {
+    match rejection.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => {
+            AppError::new(rejection.body_text(), AppErrorType::PayloadTooLarge)
+        }
+        _ => AppError::validation(rejection.body_text()),
+    }
+}
@@ -1,20 +1,39 @@
-use crate::configure::{get_config, Config};
+use crate::configure::config_reload::{reload_config, ReloadReport};
+use crate::configure::config_source::{
+    inspect_config, redacted_config, ConfigInspect, GLOBAL_CONFIG_SOURCES,
+};
+use crate::configure::get_config;
 use crate::httpserver::exception::{AppError, AppErrorType};
 use crate::httpserver::handlers::HandlerResult;
 use crate::httpserver::module::Response;
 use axum::Json;
+use serde_json::Value;
 
-pub async fn current_config() -> HandlerResult<Config> {
-    let config = get_config();
+// 密钥类字段已脱敏
+pub async fn current_config() -> HandlerResult<Value> {
+    let config = get_config().and_then(|c| redacted_config(&c));
     match config {
         Ok(cfg) => Ok(Json(Response::ok(cfg))),
-        Err(e) => {
-            let err = AppError {
-                message: Some(e.to_string()),
-                cause: None,
-                error_type: AppErrorType::UnknowErr,
-            };
-            return Err(err);
-        }
+        Err(e) => Err(AppError::classify(e, AppErrorType::UnknowErr)),
+    }
+}
+
+// 各配置项的值、来源及加载的配置文件
+pub async fn config_inspect() -> HandlerResult<ConfigInspect> {
+    let sources = match GLOBAL_CONFIG_SOURCES.read() {
+        Ok(s) => s.clone(),
+        Err(e) => return Err(AppError::new(e, AppErrorType::UnknowErr)),
+    };
+    match get_config().and_then(|c| inspect_config(&c, &sources)) {
+        Ok(inspect) => Ok(Json(Response::ok(inspect))),
+        Err(e) => Err(AppError::classify(e, AppErrorType::UnknowErr)),
+    }
+}
+
+// 重新加载配置文件，返回已生效及需重启的配置项
+pub async fn config_reload() -> HandlerResult<ReloadReport> {
+    match reload_config() {
+        Ok(report) => Ok(Json(Response::ok(report))),
+        Err(e) => Err(AppError::validation(e)),
     }
 }
@@ -0,0 +1,27 @@
+use axum::Json;
+
+use crate::{
+    chunk::{split, Chunk},
+    configure::get_config,
+    httpserver::{
+        exception::{AppError, AppErrorType},
+        extract::ReqJson,
+        module::{ReqChunk, Response},
+    },
+};
+
+use super::HandlerResult;
+
+pub async fn handler_chunk(ReqJson(req): ReqJson<ReqChunk>) -> HandlerResult<Vec<Chunk>> {
+    let splitter = match req.splitter {
+        Some(s) => s,
+        None => match get_config() {
+            Ok(c) => c.ingest.splitter,
+            Err(e) => return Err(AppError::classify(e, AppErrorType::UnknowErr)),
+        },
+    };
+    match split(&req.content, &splitter) {
+        Ok(chunks) => Ok(Json(Response::ok(chunks))),
+        Err(e) => Err(AppError::classify(e, AppErrorType::Model)),
+    }
+}
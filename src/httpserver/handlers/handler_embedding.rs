@@ -5,34 +5,37 @@ use qdrant_client::qdrant::{ScoredPoint, SearchPoints, Value};
 use uuid::Uuid;
 
 use crate::{
-    embedding::{answer::answer, embedding_setence, retriever::retriever},
+    embedding::{answer::answer, embedding_setence, reranker::rerank, retriever::retriever},
     httpserver::{
         exception::{AppError, AppErrorType},
-        module::{module_retriever::RespRetriever, ReqContent, ReqRetriever, Response},
+        extract::ReqJson,
+        module::{
+            module_retriever::{RespRerank, RespRetriever},
+            ReqContent, ReqRerank, ReqRetriever, Response,
+        },
     },
 };
 
 use super::HandlerResult;
 
-pub async fn handler_embedding(Json(req): Json<ReqContent>) -> HandlerResult<Vec<Vec<f32>>> {
+pub async fn handler_embedding(ReqJson(req): ReqJson<ReqContent>) -> HandlerResult<Vec<Vec<f32>>> {
     match embedding_setence(&req.content).await {
         Ok(token) => Ok(Json(Response::ok(token))),
-        Err(e) => {
-            let err = AppError {
-                message: Some(e.to_string()),
-                cause: None,
-                error_type: AppErrorType::UnknowErr,
-            };
-            return Err(err);
-        }
+        Err(e) => Err(AppError::classify(e, AppErrorType::Model)),
     }
 }
 
-pub async fn handler_retriever(Json(req): Json<ReqRetriever>) -> HandlerResult<Vec<RespRetriever>> {
-    match retriever(&req.content, req.limit).await {
+pub async fn handler_retriever(
+    ReqJson(req): ReqJson<ReqRetriever>,
+) -> HandlerResult<Vec<RespRetriever>> {
+    if let Err(e) = req.options.validate() {
+        return Err(AppError::validation(e));
+    }
+    match retriever(&req.content, req.limit, &req.options).await {
         Ok(r) => {
             let mut vec_resp = vec![];
-            for p in r.result {
+            for retrieved in r {
+                let p = retrieved.point;
                 let id = match p.id {
                     Some(pid) => match pid.point_id_options {
                         Some(pido) => match pido {
@@ -48,32 +51,47 @@ pub async fn handler_retriever(Json(req): Json<ReqRetriever>) -> HandlerResult<V
 
                 let payload = p.payload.clone();
                 let score = p.score;
-                let resp = RespRetriever { id, payload, score };
+                let resp = RespRetriever {
+                    id,
+                    payload,
+                    score,
+                    rerank_score: retrieved.rerank_score,
+                };
                 vec_resp.push(resp);
             }
             Ok(Json(Response::ok(vec_resp)))
         }
-        Err(e) => {
-            let err = AppError {
-                message: Some(e.to_string()),
-                cause: None,
-                error_type: AppErrorType::UnknowErr,
-            };
-            return Err(err);
+        Err(e) => Err(AppError::classify(e, AppErrorType::UnknowErr)),
+    }
+}
+
+pub async fn handler_rerank(ReqJson(req): ReqJson<ReqRerank>) -> HandlerResult<Vec<RespRerank>> {
+    match rerank(&req.query, &req.texts).await {
+        Ok(scored) => {
+            let top_n = req.top_n.unwrap_or(scored.len());
+            let vec_resp = scored
+                .into_iter()
+                .take(top_n)
+                .map(|(index, score)| RespRerank {
+                    index,
+                    text: req.texts[index].clone(),
+                    score,
+                })
+                .collect::<Vec<RespRerank>>();
+            Ok(Json(Response::ok(vec_resp)))
         }
+        Err(e) => Err(AppError::classify(e, AppErrorType::Model)),
     }
 }
 
-pub async fn handler_answer(Json(req): Json<ReqRetriever>) -> HandlerResult<String> {
-    match answer(&req.content, req.limit as usize) {
+pub async fn handler_answer(ReqJson(req): ReqJson<ReqRetriever>) -> HandlerResult<String> {
+    // 生成为同步推理，放到阻塞线程池执行，不占用 async worker
+    let generated = tokio::task::spawn_blocking(move || answer(&req.content, req.limit as usize))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|r| r);
+    match generated {
         Ok(s) => Ok(Json(Response::ok(s))),
-        Err(e) => {
-            let err = AppError {
-                message: Some(e.to_string()),
-                cause: None,
-                error_type: AppErrorType::UnknowErr,
-            };
-            return Err(err);
-        }
+        Err(e) => Err(AppError::classify(e, AppErrorType::Model)),
     }
 }
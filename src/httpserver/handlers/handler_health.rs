@@ -0,0 +1,112 @@
+use crate::configure::config_validate::CheckResult;
+use crate::configure::get_config;
+use crate::embedding::answer::GLOBAL_PIPELINE;
+use crate::embedding::reranker::GLOBAL_RERANKER;
+use crate::embedding::{model_hidden_size, GLOBAL_EMBEDDING_MODEL};
+use crate::httpserver::middleware::current_request_id;
+use crate::httpserver::module::Response;
+use crate::httpserver::GLOBAL_STARTUP;
+use crate::resources::resource_qdrant::{collection_dimension, health_check, qdrant_client};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::OnceCell;
+
+use super::HandlerResult;
+
+// 模型维度只在首次检查时读取
+static MODEL_DIMENSION: OnceCell<u64> = OnceCell::const_new();
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+/// 进程存活即返回 200
+pub async fn health_live() -> HandlerResult<Value> {
+    Ok(Json(Response::ok(json!({"health":"ok"}))))
+}
+
+/// 模型均已加载、qdrant 可访问且 collection 维度与模型一致时返回 200，否则返回 503
+pub async fn health_ready() -> (StatusCode, Json<Response<Readiness>>) {
+    let checks = readiness_checks().await;
+    let ready = checks.iter().all(|c| c.ok);
+    let readiness = Readiness { ready, checks };
+    match ready {
+        true => (StatusCode::OK, Json(Response::ok(readiness))),
+        // 错误码与 AppErrorType::Overloaded 一致
+        false => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(
+                Response::new(6, "not ready".to_string(), Some(readiness))
+                    .with_request_id(current_request_id()),
+            ),
+        ),
+    }
+}
+
+async fn readiness_checks() -> Vec<CheckResult> {
+    let config = match get_config() {
+        Ok(c) => c,
+        Err(e) => return vec![CheckResult::fail("config", e)],
+    };
+    let mut checks = vec![];
+    checks.push(match GLOBAL_STARTUP.is_finished() {
+        true => CheckResult::ok("startup", "finished"),
+        false => CheckResult::fail("startup", "loading"),
+    });
+    checks.push(match GLOBAL_EMBEDDING_MODEL.initialized() {
+        true => CheckResult::ok("embedding_model", &config.model.model_id),
+        false => CheckResult::fail("embedding_model", "not loaded"),
+    });
+    checks.push(match GLOBAL_PIPELINE.initialized() {
+        true => CheckResult::ok("generation_model", "loaded"),
+        false => CheckResult::fail("generation_model", "not loaded"),
+    });
+    if config.reranker.enable {
+        checks.push(match GLOBAL_RERANKER.initialized() {
+            true => CheckResult::ok("reranker", &config.reranker.model_id),
+            false => CheckResult::fail("reranker", "not loaded"),
+        });
+    }
+
+    if let Err(e) = health_check().await {
+        checks.push(CheckResult::fail("qdrant", e));
+        return checks;
+    }
+    checks.push(CheckResult::ok("qdrant", &config.qdrant.uri));
+
+    let collection = &config.qdrant.collection;
+    let dimension = MODEL_DIMENSION
+        .get_or_try_init(|| async {
+            model_hidden_size(&config.model.model_id, &config.model.revision)
+                .await
+                .map(|d| d as u64)
+        })
+        .await;
+    checks.push(
+        match (
+            collection_dimension(&qdrant_client(), collection).await,
+            dimension,
+        ) {
+            (Err(e), _) => CheckResult::fail("collection", format!("{}: {}", collection, e)),
+            (Ok(None), _) => CheckResult::fail("collection", format!("{} not exist", collection)),
+            (Ok(Some(_)), Err(e)) => {
+                CheckResult::fail("collection", format!("read model dimension error: {}", e))
+            }
+            (Ok(Some(d)), Ok(m)) if d != *m => CheckResult::fail(
+                "collection",
+                format!(
+                    "{} dimension {} does not match model dimension {}",
+                    collection, d, m
+                ),
+            ),
+            (Ok(Some(d)), Ok(_)) => {
+                CheckResult::ok("collection", format!("{} dimension {}", collection, d))
+            }
+        },
+    );
+    checks
+}
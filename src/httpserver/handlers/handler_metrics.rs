@@ -0,0 +1,11 @@
+use crate::metrics::GLOBAL_METRICS;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+
+/// Prometheus 文本格式的指标
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        GLOBAL_METRICS.render(),
+    )
+}
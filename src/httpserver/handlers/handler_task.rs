@@ -0,0 +1,243 @@
+use axum::{Extension, Json};
+
+use crate::{
+    commons::resolve_path_under,
+    configure::{config_auth::ConfigApiKey, get_config},
+    httpserver::{
+        exception::{AppError, AppErrorType},
+        extract::ReqJson,
+        middleware::check_collection,
+        module::{
+            module_task::{ReqTaskCreate, RespTaskStatus, TaskId, TaskIds},
+            Response,
+        },
+    },
+    ingest::{ExportJsonl, ImportJsonl, IngestFolder},
+    tasks::{TaskError, TaskMeta, TaskType, GLOBAL_TASK_MANAGER},
+};
+
+use super::HandlerResult;
+
+fn task_error(e: anyhow::Error) -> AppError {
+    match e.downcast_ref::<TaskError>() {
+        Some(TaskError::NotExist(_)) => AppError::new(e, AppErrorType::NotFound),
+        Some(_) => AppError::validation(e),
+        None => AppError::classify(e, AppErrorType::UnknowErr),
+    }
+}
+
+// 任务使用的 collection，未指定时为配置文件中的 collection
+fn task_collection(task: &TaskType) -> Result<String, AppError> {
+    match task.collection() {
+        Some(c) => Ok(c.to_string()),
+        None => Ok(get_config()
+            .map_err(|e| AppError::classify(e, AppErrorType::UnknowErr))?
+            .qdrant
+            .collection),
+    }
+}
+
+// 校验 api key 能否访问任务使用的 collection
+fn authorize_task(
+    api_key: &Option<Extension<ConfigApiKey>>,
+    task: &TaskType,
+) -> Result<(), AppError> {
+    match api_key {
+        Some(Extension(k)) => check_collection(Some(k), &task_collection(task)?),
+        None => Ok(()),
+    }
+}
+
+// 按 task_id 查找任务并校验 api key 能否访问
+fn authorized_task(
+    api_key: &Option<Extension<ConfigApiKey>>,
+    task_id: &str,
+) -> Result<TaskMeta, AppError> {
+    let meta = GLOBAL_TASK_MANAGER.show(task_id).map_err(task_error)?;
+    authorize_task(api_key, &meta.task)?;
+    Ok(meta)
+}
+
+// 列表只返回 api key 可访问的 collection 上的任务
+fn visible_tasks(
+    api_key: &Option<Extension<ConfigApiKey>>,
+    metas: Vec<TaskMeta>,
+) -> Result<Vec<TaskMeta>, AppError> {
+    let api_key = match api_key {
+        Some(Extension(k)) => k,
+        None => return Ok(metas),
+    };
+    let mut visible = vec![];
+    for meta in metas {
+        if api_key.collection_allowed(&task_collection(&meta.task)?) {
+            visible.push(meta);
+        }
+    }
+    Ok(visible)
+}
+
+// 任务读写的服务端文件限制在 task.file_root 下，路径替换为解析后的绝对路径
+fn confine_task_paths(task: TaskType) -> Result<TaskType, AppError> {
+    let root = get_config()
+        .map_err(|e| AppError::classify(e, AppErrorType::UnknowErr))?
+        .task
+        .file_root;
+    let resolve = |path: &str| -> Result<String, AppError> {
+        resolve_path_under(&root, path)
+            .map(|p| p.display().to_string())
+            .map_err(|e| AppError::new(e, AppErrorType::Forbidden))
+    };
+    let task = match task {
+        TaskType::Ingest(mut ingest) => {
+            ingest.folder = resolve(&ingest.folder)?;
+            TaskType::Ingest(ingest)
+        }
+        TaskType::Import(mut import) => {
+            import.file = resolve(&import.file)?;
+            TaskType::Import(import)
+        }
+        TaskType::Export(mut export) => {
+            if let Some(file) = &export.file {
+                export.file = Some(resolve(file)?);
+            }
+            TaskType::Export(export)
+        }
+        TaskType::Reembed(reembed) => TaskType::Reembed(reembed),
+    };
+    Ok(task)
+}
+
+pub async fn task_create(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(req): ReqJson<ReqTaskCreate>,
+) -> HandlerResult<TaskId> {
+    authorize_task(&api_key, &req.task)?;
+    let task = confine_task_paths(req.task)?;
+    match GLOBAL_TASK_MANAGER.create(req.name, task) {
+        Ok(task_id) => Ok(Json(Response::ok(TaskId { task_id }))),
+        Err(e) => Err(task_error(e)),
+    }
+}
+
+pub async fn task_start(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(req): ReqJson<TaskId>,
+) -> HandlerResult<()> {
+    authorized_task(&api_key, &req.task_id)?;
+    match GLOBAL_TASK_MANAGER.start(&req.task_id) {
+        Ok(_) => Ok(Json(Response::ok(()))),
+        Err(e) => Err(task_error(e)),
+    }
+}
+
+pub async fn task_stop(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(req): ReqJson<TaskId>,
+) -> HandlerResult<()> {
+    authorized_task(&api_key, &req.task_id)?;
+    match GLOBAL_TASK_MANAGER.stop(&req.task_id) {
+        Ok(_) => Ok(Json(Response::ok(()))),
+        Err(e) => Err(task_error(e)),
+    }
+}
+
+pub async fn task_status(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(req): ReqJson<TaskId>,
+) -> HandlerResult<RespTaskStatus> {
+    let meta = authorized_task(&api_key, &req.task_id)?;
+    Ok(Json(Response::ok(RespTaskStatus {
+        task_id: meta.task_id,
+        status: meta.status,
+        progress: meta.progress,
+        message: meta.message,
+    })))
+}
+
+pub async fn task_show(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(req): ReqJson<TaskId>,
+) -> HandlerResult<TaskMeta> {
+    let meta = authorized_task(&api_key, &req.task_id)?;
+    Ok(Json(Response::ok(meta)))
+}
+
+pub async fn task_all(api_key: Option<Extension<ConfigApiKey>>) -> HandlerResult<Vec<TaskMeta>> {
+    match GLOBAL_TASK_MANAGER.all() {
+        Ok(metas) => Ok(Json(Response::ok(visible_tasks(&api_key, metas)?))),
+        Err(e) => Err(task_error(e)),
+    }
+}
+
+pub async fn task_all_living(
+    api_key: Option<Extension<ConfigApiKey>>,
+) -> HandlerResult<Vec<TaskMeta>> {
+    match GLOBAL_TASK_MANAGER.all_living() {
+        Ok(metas) => Ok(Json(Response::ok(visible_tasks(&api_key, metas)?))),
+        Err(e) => Err(task_error(e)),
+    }
+}
+
+pub async fn task_remove(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(req): ReqJson<TaskIds>,
+) -> HandlerResult<()> {
+    // 不存在的任务 id 与原来一样忽略
+    for task_id in req.task_ids.iter() {
+        if let Ok(meta) = GLOBAL_TASK_MANAGER.show(task_id) {
+            authorize_task(&api_key, &meta.task)?;
+        }
+    }
+    match GLOBAL_TASK_MANAGER.remove(&req.task_ids) {
+        Ok(_) => Ok(Json(Response::ok(()))),
+        Err(e) => Err(task_error(e)),
+    }
+}
+
+fn create_and_start(
+    api_key: Option<Extension<ConfigApiKey>>,
+    name: String,
+    task: TaskType,
+) -> HandlerResult<TaskId> {
+    authorize_task(&api_key, &task)?;
+    let task = confine_task_paths(task)?;
+    let r = GLOBAL_TASK_MANAGER
+        .create(name, task)
+        .and_then(|task_id| GLOBAL_TASK_MANAGER.start(&task_id).map(|_| task_id));
+    match r {
+        Ok(task_id) => Ok(Json(Response::ok(TaskId { task_id }))),
+        Err(e) => Err(task_error(e)),
+    }
+}
+
+// 创建并立即启动目录入库任务
+pub async fn task_ingest(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(ingest): ReqJson<IngestFolder>,
+) -> HandlerResult<TaskId> {
+    create_and_start(
+        api_key,
+        format!("ingest {}", ingest.folder),
+        TaskType::Ingest(ingest),
+    )
+}
+
+pub async fn task_import(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(import): ReqJson<ImportJsonl>,
+) -> HandlerResult<TaskId> {
+    create_and_start(
+        api_key,
+        format!("import {}", import.file),
+        TaskType::Import(import),
+    )
+}
+
+// 导出到服务端文件
+pub async fn task_export(
+    api_key: Option<Extension<ConfigApiKey>>,
+    ReqJson(export): ReqJson<ExportJsonl>,
+) -> HandlerResult<TaskId> {
+    let name = format!("export {}", export.file.clone().unwrap_or_default());
+    create_and_start(api_key, name, TaskType::Export(export))
+}
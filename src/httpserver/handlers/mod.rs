@@ -1,11 +1,19 @@
 mod config;
+mod handler_chunk;
 mod handler_embedding;
+mod handler_health;
+mod handler_metrics;
 mod handler_root;
+mod handler_task;
 
 use crate::httpserver::module::Response;
 use axum::Json;
-pub use config::current_config;
+pub use config::{config_inspect, config_reload, current_config};
+pub use handler_chunk::*;
 pub use handler_embedding::*;
+pub use handler_health::{health_live, health_ready};
+pub use handler_metrics::metrics;
 pub use handler_root::root;
+pub use handler_task::*;
 
 type HandlerResult<T> = crate::httpserver::module::Result<Json<Response<T>>>;
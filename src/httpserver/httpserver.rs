@@ -1,33 +1,175 @@
+use crate::configure::config_http::{ConfigHttp, ListenAddr};
 use crate::httpserver::routers::router_root;
+use crate::httpserver::shutdown::GLOBAL_SHUTDOWN;
+use anyhow::{anyhow, Result};
+use axum::extract::Request;
 use axum::Router;
-use tokio::net::TcpListener;
+use hyper::body::Incoming;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use std::time::Duration;
+use tokio::net::{TcpListener, UnixListener, UnixStream};
 use tokio::spawn;
-use tokio::task::JoinHandle;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::sleep;
+use tower::Service;
+
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+pub enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
 
 pub struct HttpServer {
-    pub listener: TcpListener,
+    pub listeners: Vec<(ListenAddr, Listener)>,
     pub router: Router,
+    pub drain_timeout: Duration,
 }
 
 impl HttpServer {
-    pub async fn default() -> Self {
-        // let port: u16 = 3000;
-        // let addr_ipv4 = net::SocketAddr::from((net::Ipv4Addr::UNSPECIFIED, port));
-        // let band_addr = SocketAddr::from(addr_ipv4);
-
-        let listener = TcpListener::bind("0.0.0.0:8080").await.unwrap();
-        let router_root = router_root();
-        Self {
-            listener,
-            router: router_root,
+    /// 按配置绑定全部监听地址，任一地址绑定失败即返回错误
+    pub async fn from_config(config: &ConfigHttp) -> Result<Self> {
+        let mut listeners = vec![];
+        for addr in config.listen_addrs()? {
+            let listener = bind(&addr)
+                .await
+                .map_err(|e| anyhow!("bind {} error: {}", addr, e))?;
+            listeners.push((addr, listener));
         }
+        // with_state 提前将 handler 转为 Route，否则 unix socket 直接 call 时
+        // 每个请求都会重新构建 layer，并发限制不共享
+        Ok(Self {
+            listeners,
+            router: router_root(&config.limits).with_state(()),
+            drain_timeout: Duration::from_secs(config.drain_timeout_secs),
+        })
     }
+
+    /// 启动全部监听，返回的 JoinHandle 在 drain 完成或超时后结束，退出前删除 unix socket 文件
     pub async fn run(self) -> JoinHandle<()> {
-        let server = axum::serve(self.listener, self.router.into_make_service());
-        let handle = spawn(async {
-            server.await.unwrap();
+        let mut handles = vec![];
+        let mut socket_files = vec![];
+        for (addr, listener) in self.listeners {
+            let router = self.router.clone();
+            log::info!("httpserver listen on {}", addr);
+            if let ListenAddr::Unix(path) = &addr {
+                socket_files.push(path.clone());
+            }
+            let handle = match listener {
+                Listener::Tcp(l) => spawn(async move {
+                    if let Err(e) = axum::serve(l, router.into_make_service())
+                        .with_graceful_shutdown(GLOBAL_SHUTDOWN.wait())
+                        .await
+                    {
+                        log::error!("httpserver {} error: {}", addr, e);
+                    }
+                }),
+                Listener::Unix(l) => spawn(serve_unix(l, router)),
+            };
+            handles.push(handle);
+        }
+        let drain_timeout = self.drain_timeout;
+        let handle = spawn(async move {
+            let drained = async {
+                for h in handles {
+                    let _ = h.await;
+                }
+            };
+            let deadline = async {
+                GLOBAL_SHUTDOWN.wait().await;
+                sleep(GLOBAL_SHUTDOWN.remaining(drain_timeout)).await;
+            };
+            tokio::select! {
+                _ = drained => log::info!("httpserver drained"),
+                _ = deadline => log::warn!(
+                    "httpserver drain timeout after {}s, in-flight requests dropped",
+                    drain_timeout.as_secs()
+                ),
+            }
+            for path in socket_files {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    log::error!("remove socket file {} error: {}", path.display(), e);
+                }
+            }
         });
         log::info!("httpserver start");
         return handle;
     }
 }
+
+async fn bind(addr: &ListenAddr) -> Result<Listener> {
+    match addr {
+        ListenAddr::Tcp(socket_addr) => Ok(Listener::Tcp(TcpListener::bind(socket_addr).await?)),
+        ListenAddr::Unix(path) => {
+            remove_stale_socket(path).await?;
+            Ok(Listener::Unix(UnixListener::bind(path)?))
+        }
+    }
+}
+
+// 上次退出未清理的 socket 文件无人监听时删除，仍有进程监听时报错
+async fn remove_stale_socket(path: &Path) -> Result<()> {
+    // 不跟随符号链接；只删除 socket 文件，避免配置错误时误删普通文件
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(anyhow!(
+            "{} exists and is not a unix socket",
+            path.display()
+        ));
+    }
+    if UnixStream::connect(path).await.is_ok() {
+        return Err(anyhow!("address already in use"));
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+// axum::serve 只支持 TcpListener，unix socket 直接用 hyper 处理连接。
+// drain 时停止 accept，等待已有连接处理完当前请求
+async fn serve_unix(listener: UnixListener, router: Router) {
+    let mut connections = JoinSet::new();
+    loop {
+        let socket = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((socket, _)) => socket,
+                Err(e) => {
+                    // 如 fd 耗尽时 accept 会立即重复失败，稍等再重试避免空转
+                    log::error!("unix socket accept error: {}", e);
+                    sleep(ACCEPT_ERROR_BACKOFF).await;
+                    continue;
+                }
+            },
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            _ = GLOBAL_SHUTDOWN.wait() => break,
+        };
+        let router = router.clone();
+        connections.spawn(async move {
+            // Router 始终 ready，可直接 call
+            let service = hyper::service::service_fn(move |request: Request<Incoming>| {
+                router.clone().call(request)
+            });
+            let builder = Builder::new(TokioExecutor::new());
+            let conn = builder.serve_connection_with_upgrades(TokioIo::new(socket), service);
+            tokio::pin!(conn);
+            let result = tokio::select! {
+                r = conn.as_mut() => r,
+                _ = GLOBAL_SHUTDOWN.wait() => {
+                    conn.as_mut().graceful_shutdown();
+                    conn.await
+                }
+            };
+            if let Err(e) = result {
+                log::debug!("unix socket connection error: {}", e);
+            }
+        });
+    }
+    drop(listener);
+    while connections.join_next().await.is_some() {}
+}
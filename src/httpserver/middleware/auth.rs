@@ -0,0 +1,70 @@
+use crate::{
+    configure::{
+        config_auth::{ConfigApiKey, Scope},
+        get_config,
+    },
+    httpserver::exception::{AppError, AppErrorType},
+};
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+
+/// 校验 api key 及 scope，通过后将 key 的配置放入 request extensions，供 handler 校验 collection
+pub async fn require_scope(
+    State(scope): State<Scope>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let config = get_config().map_err(|e| AppError::classify(e, AppErrorType::UnknowErr))?;
+    if !config.auth.enable {
+        return Ok(next.run(req).await);
+    }
+    let key = request_api_key(req.headers())
+        .ok_or_else(|| AppError::new("missing api key", AppErrorType::Unauthorized))?;
+    let api_key = config
+        .auth
+        .find_key(&key)
+        .cloned()
+        .ok_or_else(|| AppError::new("invalid api key", AppErrorType::Unauthorized))?;
+    if !api_key.has_scope(scope) {
+        return Err(AppError::new(
+            format!("api key {} has no {:?} scope", api_key.name, scope),
+            AppErrorType::Forbidden,
+        ));
+    }
+    // 检索及生成使用配置文件中的 collection
+    if matches!(scope, Scope::Retrieve | Scope::Generate) {
+        check_collection(Some(&api_key), &config.qdrant.collection)?;
+    }
+    req.extensions_mut().insert(api_key);
+    Ok(next.run(req).await)
+}
+
+/// 未开启认证时 api_key 为 None，不做限制
+pub fn check_collection(api_key: Option<&ConfigApiKey>, collection: &str) -> Result<(), AppError> {
+    match api_key {
+        Some(k) if !k.collection_allowed(collection) => Err(AppError::new(
+            format!(
+                "api key {} can not access collection {}",
+                k.name, collection
+            ),
+            AppErrorType::Forbidden,
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn request_api_key(headers: &HeaderMap) -> Option<String> {
+    if let Some(v) = headers.get(AUTHORIZATION).and_then(|v| v.to_str().ok()) {
+        if let Some(key) = v.strip_prefix("Bearer ") {
+            return Some(key.trim().to_string());
+        }
+    }
+    headers
+        .get("x-api-key")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+}
@@ -0,0 +1,28 @@
+use crate::httpserver::{
+    exception::{AppError, AppErrorType},
+    shutdown::GLOBAL_SHUTDOWN,
+    startup::GLOBAL_STARTUP,
+};
+use axum::{extract::Request, middleware::Next, response::Response};
+
+/// drain 期间拒绝新请求，返回 503
+pub async fn reject_when_draining(req: Request, next: Next) -> Result<Response, AppError> {
+    if GLOBAL_SHUTDOWN.is_draining() {
+        return Err(AppError::new(
+            "server is shutting down",
+            AppErrorType::Overloaded,
+        ));
+    }
+    Ok(next.run(req).await)
+}
+
+/// 启动加载完成前拒绝请求，返回 503，避免任务被加载覆盖或模型未加载时返回 500
+pub async fn reject_until_started(req: Request, next: Next) -> Result<Response, AppError> {
+    if !GLOBAL_STARTUP.is_finished() {
+        return Err(AppError::new(
+            "server is starting",
+            AppErrorType::Unavailable,
+        ));
+    }
+    Ok(next.run(req).await)
+}
@@ -0,0 +1,31 @@
+use crate::metrics::GLOBAL_METRICS;
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+
+/// 按路由模板统计请求数及耗时，未匹配的路径统一记为 unmatched，避免标签数量无限增长
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched".to_string(), |p| p.as_str().to_string());
+    let method = req.method().to_string();
+    let start = Instant::now();
+    let in_flight = GLOBAL_METRICS.enter_http();
+    let response = next.run(req).await;
+    drop(in_flight);
+
+    let status = response.status().as_u16().to_string();
+    GLOBAL_METRICS
+        .http_requests
+        .with(&[&method, &route, &status])
+        .inc();
+    GLOBAL_METRICS
+        .http_request_duration
+        .with(&[&method, &route])
+        .observe(start.elapsed().as_secs_f64());
+    response
+}
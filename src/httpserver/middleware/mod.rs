@@ -0,0 +1,8 @@
+mod auth;
+mod drain;
+mod metrics;
+mod request_id;
+pub use auth::*;
+pub use drain::*;
+pub use metrics::*;
+pub use request_id::*;
@@ -0,0 +1,55 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 沿用客户端传入的 X-Request-Id，没有或不合法时生成 uuid。
+/// 写回请求头供 TraceLayer 记入 span，并在响应头中返回
+pub async fn request_id(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| valid_request_id(v))
+        .map_or_else(|| Uuid::new_v4().to_string(), |v| v.to_string());
+    // id 均为可见 ASCII 字符，必然是合法的 header 值
+    let value = HeaderValue::from_str(&id).unwrap();
+    req.headers_mut()
+        .insert(X_REQUEST_ID.clone(), value.clone());
+    let mut response = REQUEST_ID.scope(id, next.run(req)).await;
+    response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+    response
+}
+
+/// 当前请求的 id，在请求处理之外调用时为 None
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+fn valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 128 && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod test {
+    use super::valid_request_id;
+
+    //cargo test httpserver::middleware::request_id::test::test_valid_request_id -- --nocapture
+    #[test]
+    fn test_valid_request_id() {
+        assert!(valid_request_id("3f2b6c1e-0c1d-4a53-9a43-6c2b0b1f1a2e"));
+        assert!(valid_request_id("req-123"));
+        assert!(!valid_request_id(""));
+        assert!(!valid_request_id("a b"));
+        assert!(!valid_request_id(&"x".repeat(129)));
+    }
+}
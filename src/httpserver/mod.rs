@@ -1,7 +1,13 @@
 pub use httpserver::HttpServer;
+pub use shutdown::GLOBAL_SHUTDOWN;
+pub use startup::GLOBAL_STARTUP;
 mod exception;
+mod extract;
 mod handlers;
 mod httpserver;
+mod middleware;
 pub(crate) mod module;
 mod routers;
 mod service;
+mod shutdown;
+mod startup;
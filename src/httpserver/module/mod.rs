@@ -1,6 +1,6 @@
 mod common_module;
 pub mod module_retriever;
-mod module_task;
+pub mod module_task;
 mod request_module;
 mod response_module;
 
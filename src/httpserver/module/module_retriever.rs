@@ -8,4 +8,13 @@ pub struct RespRetriever {
     pub id: String,
     pub payload: HashMap<String, Value>,
     pub score: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct RespRerank {
+    pub index: usize,
+    pub text: String,
+    pub score: f32,
 }
@@ -1,3 +1,4 @@
+use crate::tasks::{ProgressSnapshot, TaskStatus, TaskType};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
@@ -9,3 +10,18 @@ pub struct TaskId {
 pub struct TaskIds {
     pub task_ids: Vec<String>,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReqTaskCreate {
+    pub name: String,
+    #[serde(flatten)]
+    pub task: TaskType,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RespTaskStatus {
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub progress: ProgressSnapshot,
+    pub message: Option<String>,
+}
@@ -1,3 +1,5 @@
+use crate::chunk::Splitter;
+use crate::embedding::retriever::RetrieverOptions;
 use serde::Deserialize;
 use strum_macros::{Display, EnumString};
 
@@ -24,4 +26,21 @@ pub struct ReqContent {
 pub struct ReqRetriever {
     pub content: String,
     pub limit: u64,
+    #[serde(flatten)]
+    pub options: RetrieverOptions,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReqRerank {
+    pub query: String,
+    pub texts: Vec<String>,
+    pub top_n: std::option::Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReqChunk {
+    pub content: String,
+    // 缺省时使用配置文件 ingest.splitter
+    #[serde(default)]
+    pub splitter: std::option::Option<Splitter>,
 }
@@ -6,6 +6,9 @@ pub struct Response<T: Serialize> {
     pub code: i32,
     pub msg: String,
     pub data: Option<T>,
+    // 仅错误响应携带，与响应头 X-Request-Id 一致
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl<T> Response<T>
@@ -13,7 +16,12 @@ where
     T: Serialize,
 {
     pub fn new(code: i32, msg: String, data: Option<T>) -> Self {
-        Self { code, msg, data }
+        Self {
+            code,
+            msg,
+            data,
+            request_id: None,
+        }
     }
     pub fn ok(data: T) -> Self {
         Self::new(0, "OK".to_string(), Some(data))
@@ -21,4 +29,8 @@ where
     pub fn err(code: i32, msg: String) -> Self {
         Self::new(code, msg, None)
     }
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id;
+        self
+    }
 }
@@ -1,54 +1,208 @@
 use crate::httpserver::handlers::{
-    current_config, handler_answer, handler_embedding, handler_retriever, root,
+    config_inspect, config_reload, current_config, handler_answer, handler_chunk,
+    handler_embedding, handler_rerank, handler_retriever, health_live, health_ready, metrics, root,
+    task_all, task_all_living, task_create, task_export, task_import, task_ingest, task_remove,
+    task_show, task_start, task_status, task_stop,
 };
 
+use crate::configure::config_auth::Scope;
+use crate::configure::config_http::{ConfigHttpLimits, ConfigRouteLimit};
+use crate::httpserver::exception::{AppError, AppErrorType};
+use crate::httpserver::middleware::{
+    reject_until_started, reject_when_draining, request_id, require_scope, track_metrics,
+    X_REQUEST_ID,
+};
 use axum::error_handling::HandleErrorLayer;
-use axum::http::StatusCode;
+use axum::extract::{DefaultBodyLimit, Request};
+use axum::middleware;
 use axum::routing::{get, post};
 use axum::{BoxError, Router};
 
 use std::time::Duration;
+use tower::limit::{ConcurrencyLimitLayer, GlobalConcurrencyLimitLayer};
+use tower::load_shed::LoadShedLayer;
+use tower::timeout::TimeoutLayer;
 use tower::ServiceBuilder;
-use tower_http::{compression::CompressionLayer, trace::TraceLayer};
+use tower_http::{
+    compression::CompressionLayer,
+    trace::{DefaultOnResponse, TraceLayer},
+};
+use tracing::Level;
 
-pub fn router_root() -> Router {
-    let tracer = TraceLayer::new_for_http();
+pub fn router_root(limits: &ConfigHttpLimits) -> Router {
+    // request_id 由外层 request_id 中间件写入请求头，span 内的全部日志均带有该字段
+    let tracer = TraceLayer::new_for_http()
+        .make_span_with(|req: &Request| {
+            let request_id = req
+                .headers()
+                .get(&X_REQUEST_ID)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            tracing::info_span!(
+                "request",
+                request_id = %request_id,
+                method = %req.method(),
+                uri = %req.uri(),
+            )
+        })
+        .on_response(DefaultOnResponse::new().level(Level::INFO));
     let middleware_stack = ServiceBuilder::new()
         .layer(tracer)
         .layer(CompressionLayer::new())
-        .layer(HandleErrorLayer::new(handle_timeout_error))
-        .layer(tower::timeout::TimeoutLayer::new(Duration::from_secs(2)))
         .into_inner();
 
     let root = Router::new()
         .route("/health", get(root))
-        .route("/health", post(root));
+        .route("/health", post(root))
+        .route("/health/live", get(health_live))
+        .route("/health/ready", get(health_ready))
+        .route("/metrics", get(metrics));
+    let root = with_limits(root, &limits.health, None);
 
     let task_router = Router::new()
-        // .route(
-        //     "/template/transfer/oss2oss",
-        //     get(task_template_transfer_oss2oss),
-        // )
-        .layer(middleware_stack.clone());
+        .route("/create", post(task_create))
+        .route("/start", post(task_start))
+        .route("/stop", post(task_stop))
+        .route("/status", post(task_status))
+        .route("/show", post(task_show))
+        .route("/all", post(task_all))
+        .route("/all_living", post(task_all_living))
+        .route("/remove", post(task_remove))
+        .route("/ingest", post(task_ingest))
+        .route("/import", post(task_import))
+        .route("/export", post(task_export));
+    // .route(
+    //     "/template/transfer/oss2oss",
+    //     get(task_template_transfer_oss2oss),
+    // )
+    // 全局并发上限，各路由组共享同一个信号量；加在认证之内，未通过认证的请求不占用额度
+    let global = (limits.max_concurrency > 0)
+        .then(|| GlobalConcurrencyLimitLayer::new(limits.max_concurrency));
+    let global = global.as_ref();
 
-    let api = Router::new()
+    let task_router = with_scope(with_limits(task_router, &limits.task, global), Scope::Write);
+
+    let config_router = Router::new()
         .route("/v1/currentconfig", post(current_config))
+        .route("/v1/config/inspect", post(config_inspect))
+        .route("/v1/config/reload", post(config_reload));
+    let embedding_router = Router::new()
         .route("/v1/embedding", post(handler_embedding))
+        .route("/v1/chunk", post(handler_chunk));
+    let retrieval_router = Router::new()
         .route("/v1/retriever", post(handler_retriever))
-        .route("/v1/answer", post(handler_answer))
-        .layer(middleware_stack.clone())
-        .nest("/v1/task", task_router);
+        .route("/v1/rerank", post(handler_rerank));
+    let generation_router = Router::new().route("/v1/answer", post(handler_answer));
 
-    return root.nest("/api", api);
+    let api = Router::new()
+        .merge(with_scope(
+            with_limits(config_router, &limits.health, global),
+            Scope::Admin,
+        ))
+        .merge(with_scope(
+            with_limits(embedding_router, &limits.embedding, global),
+            Scope::Embed,
+        ))
+        .merge(with_scope(
+            with_limits(retrieval_router, &limits.retrieval, global),
+            Scope::Retrieve,
+        ))
+        .merge(with_scope(
+            with_limits(generation_router, &limits.generation, global),
+            Scope::Generate,
+        ))
+        .nest("/v1/task", task_router)
+        .layer(DefaultBodyLimit::max(limits.max_body_size))
+        // 健康检查及 metrics 不在 /api 下，启动期间仍可访问
+        .layer(middleware::from_fn(reject_until_started));
+
+    // drain 期间的新请求直接返回 503，统计在其外层以计入被拒绝的请求，
+    // request_id 在最外层，被拒绝的请求同样带有 X-Request-Id
+    root.nest("/api", api)
+        .layer(middleware_stack)
+        .layer(middleware::from_fn(reject_when_draining))
+        .layer(middleware::from_fn(track_metrics))
+        .layer(middleware::from_fn(request_id))
+}
+
+// 认证在 with_limits 的全部限流之外，未通过认证的请求不占用并发额度
+fn with_scope(router: Router, scope: Scope) -> Router {
+    router.route_layer(middleware::from_fn_with_state(scope, require_scope))
 }
 
-async fn handle_timeout_error(err: BoxError) -> (StatusCode, String) {
+// 为一组路由加上超时、单路由并发限制及全局并发限制
+fn with_limits(
+    router: Router,
+    limit: &ConfigRouteLimit,
+    global: Option<&GlobalConcurrencyLimitLayer>,
+) -> Router {
+    let mut router = router;
+    if limit.max_in_flight > 0 {
+        router = router.layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(handle_error))
+                .layer(LoadShedLayer::new())
+                .layer(ConcurrencyLimitLayer::new(limit.max_in_flight)),
+        );
+    }
+    if limit.timeout_secs > 0 {
+        router = router.layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(handle_error))
+                .layer(TimeoutLayer::new(Duration::from_secs(limit.timeout_secs))),
+        );
+    }
+    if let Some(global) = global {
+        router = router.layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(handle_error))
+                .layer(LoadShedLayer::new())
+                .layer(global.clone()),
+        );
+    }
+    router
+}
+
+async fn handle_error(err: BoxError) -> AppError {
     if err.is::<tower::timeout::error::Elapsed>() {
-        (StatusCode::REQUEST_TIMEOUT, "Request timeout".to_string())
+        AppError::new("Request timeout", AppErrorType::Timeout)
+    } else if err.is::<tower::load_shed::error::Overloaded>() {
+        AppError::new("Too many requests in flight", AppErrorType::Overloaded)
     } else {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
+        AppError::new(
             format!("Unhandled internal error: {}", err),
+            AppErrorType::UnknowErr,
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::router_root;
+    use crate::configure::config_http::ConfigHttpLimits;
+    use crate::httpserver::GLOBAL_STARTUP;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::Service;
+
+    //cargo test httpserver::routers::root::test::test_body_limit -- --nocapture
+    #[test]
+    fn test_body_limit() {
+        GLOBAL_STARTUP.finish();
+        let limits = ConfigHttpLimits {
+            max_body_size: 16,
+            ..Default::default()
+        };
+        let mut router = router_root(&limits);
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/v1/chunk")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(vec![b' '; limits.max_body_size + 1]))
+            .unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let res = rt.block_on(router.call(req)).unwrap();
+        println!("{:?}", res);
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,74 @@
+use once_cell::sync::Lazy;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// 进程退出状态，收到终止信号后进入 drain，停止接受新连接并等待处理中的请求完成
+pub static GLOBAL_SHUTDOWN: Lazy<Shutdown> = Lazy::new(Shutdown::new);
+
+pub struct Shutdown {
+    sender: watch::Sender<bool>,
+    // 开始 drain 的时间，drain 超时按此计算
+    started_at: OnceLock<Instant>,
+}
+
+impl Shutdown {
+    fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender,
+            started_at: OnceLock::new(),
+        }
+    }
+
+    /// 开始 drain，返回 false 表示此前已开始
+    pub fn start(&self) -> bool {
+        if self.started_at.set(Instant::now()).is_err() {
+            return false;
+        }
+        self.sender.send_replace(true);
+        true
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.started_at.get().is_some()
+    }
+
+    /// 等待开始 drain
+    pub async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        let _ = receiver.wait_for(|draining| *draining).await;
+    }
+
+    /// drain 超时前剩余的时间，未开始 drain 时为 timeout
+    pub fn remaining(&self, timeout: Duration) -> Duration {
+        match self.started_at.get() {
+            Some(t) => timeout.saturating_sub(t.elapsed()),
+            None => timeout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Shutdown;
+    use std::time::Duration;
+
+    //cargo test httpserver::shutdown::test::test_shutdown -- --nocapture
+    #[test]
+    fn test_shutdown() {
+        let shutdown = Shutdown::new();
+        let timeout = Duration::from_secs(30);
+        assert!(!shutdown.is_draining());
+        assert_eq!(shutdown.remaining(timeout), timeout);
+        assert!(shutdown.start());
+        assert!(!shutdown.start());
+        assert!(shutdown.is_draining());
+        assert!(shutdown.remaining(timeout) <= timeout);
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            tokio::time::timeout(Duration::from_secs(1), shutdown.wait())
+                .await
+                .unwrap()
+        });
+    }
+}
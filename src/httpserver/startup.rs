@@ -0,0 +1,26 @@
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 启动状态。http server 先于模型及任务加载启动，加载完成前 /api 下的接口返回 503
+pub static GLOBAL_STARTUP: Lazy<Startup> = Lazy::new(Startup::new);
+
+pub struct Startup {
+    finished: AtomicBool,
+}
+
+impl Startup {
+    fn new() -> Self {
+        Self {
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    /// 资源、模型及持久化任务均已加载
+    pub fn finish(&self) {
+        self.finished.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+}
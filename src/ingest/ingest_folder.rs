@@ -0,0 +1,286 @@
+use super::ingest_sync::{changed_fields, chunk_point_id, content_hash, SyncCounts};
+use crate::{
+    chunk::split,
+    commons::{read_lines, scan_folder_files, LastModifyFilter, RegexFilter},
+    configure::{config_ingest::ConfigIngest, get_config},
+    embedding::{embedding_batch, embedding_setence},
+    resources::resource_qdrant::{
+        delete_points_by_ids, ensure_collection, point_id_from_string, point_id_to_string,
+        scroll_point_payloads, set_payload_by_ids, set_payloads, upsert_points,
+    },
+    tasks::TaskProgress,
+};
+use anyhow::{anyhow, Result};
+use qdrant_client::{
+    qdrant::{Condition, Filter, PointId, PointStruct, Value},
+    Payload,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::atomic::Ordering,
+};
+
+// 随 chunk 在文件中的位置变化、内容未变时也需更新的 payload 字段
+const POSITION_FIELDS: [&str; 4] = ["chunk_index", "mtime", "heading", "line"];
+
+/// 目录入库参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestFolder {
+    pub folder: String,
+    #[serde(default)]
+    pub include_regex: Option<Vec<String>>,
+    #[serde(default)]
+    pub exclude_regex: Option<Vec<String>>,
+    #[serde(default)]
+    pub last_modify_filter: Option<LastModifyFilter>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestReport {
+    pub files: usize,
+    pub chunks: SyncCounts,
+    pub failed_files: Vec<String>,
+}
+
+/// 待入库的文档，一个 txt/md 文件为一个文档，jsonl 文件每行一个文档
+struct Document {
+    text: String,
+    // jsonl 行号，其他文件为 None
+    line: Option<usize>,
+}
+
+impl IngestFolder {
+    pub async fn execute(&self) -> Result<IngestReport> {
+        self.execute_with_progress(&TaskProgress::default()).await
+    }
+
+    /// 按路径顺序入库，每个文件处理完后记录断点，重新启动时跳过断点及之前的文件
+    pub async fn execute_with_progress(&self, progress: &TaskProgress) -> Result<IngestReport> {
+        let config = get_config()?;
+        let folder = source_path(&self.folder)?;
+        let regex_filter = RegexFilter::from_vec(&self.exclude_regex, &self.include_regex)?;
+        let mut files = scan_folder_files(&folder, Some(regex_filter), self.last_modify_filter)?
+            .into_iter()
+            .filter(|(path, _)| extension_allowed(path, &config.ingest.extensions))
+            .collect::<Vec<(String, u64)>>();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        progress.total.store(files.len() as u64, Ordering::SeqCst);
+
+        let checkpoint = progress.checkpoint();
+        let mut report = IngestReport::default();
+        let mut collection_checked = false;
+        for (path, mtime) in files {
+            if progress.is_stopped() {
+                break;
+            }
+            if checkpoint
+                .as_ref()
+                .is_some_and(|c| path.as_str() <= c.as_str())
+            {
+                continue;
+            }
+            if !collection_checked {
+                prepare_collection(&config.qdrant.collection).await?;
+                collection_checked = true;
+            }
+            match sync_file(&path, mtime, &config.qdrant.collection, &config.ingest).await {
+                Ok(counts) => {
+                    report.files += 1;
+                    report.chunks += counts;
+                    progress.processed.fetch_add(1, Ordering::SeqCst);
+                }
+                Err(e) => {
+                    log::error!("ingest file {} error: {}", path, e);
+                    progress.failed.fetch_add(1, Ordering::SeqCst);
+                    report.failed_files.push(path.clone());
+                }
+            }
+            progress.set_checkpoint(path);
+        }
+        log::info!("ingest folder {} finished: {:?}", self.folder, report);
+        Ok(report)
+    }
+}
+
+/// 来源路径统一为规范化的绝对路径，http、命令行及目录监听入库的同一文件 path 及 chunk id 一致
+pub(crate) fn source_path(path: &str) -> Result<String> {
+    let canonical =
+        std::fs::canonicalize(path).map_err(|e| anyhow!("resolve path {} error: {}", path, e))?;
+    Ok(canonical.display().to_string())
+}
+
+/// 以模型输出维度创建 collection
+pub(crate) async fn prepare_collection(collection: &str) -> Result<()> {
+    let dimension = embedding_setence("dimension")
+        .await?
+        .first()
+        .map(|v| v.len())
+        .ok_or_else(|| anyhow!("empty embedding"))?;
+    ensure_collection(collection, dimension as u64).await
+}
+
+pub(crate) fn extension_allowed(path: &str, extensions: &[String]) -> bool {
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some(ext) => extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)),
+        None => false,
+    }
+}
+
+fn read_documents(path: &str, config: &ConfigIngest) -> Result<Vec<Document>> {
+    let is_jsonl = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("jsonl"));
+
+    if !is_jsonl {
+        let text = std::fs::read_to_string(path)?;
+        return Ok(vec![Document { text, line: None }]);
+    }
+
+    let mut docs = vec![];
+    for (idx, line) in read_lines(path)?.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(&line)?;
+        match value.get(&config.jsonl_text_field).and_then(|v| v.as_str()) {
+            Some(text) => docs.push(Document {
+                text: text.to_string(),
+                line: Some(idx),
+            }),
+            None => log::warn!(
+                "{}:{} has no text field {}",
+                path,
+                idx + 1,
+                config.jsonl_text_field
+            ),
+        }
+    }
+    Ok(docs)
+}
+
+/// 增量同步单个文件，以文件路径为来源 id。
+/// 内容未变化的 chunk 保留原向量，只更新 chunk_index、mtime 等位置相关的 payload；
+/// 变化的 chunk 按批计算向量写入，文件中已不存在的 chunk 删除
+pub(crate) async fn sync_file(
+    path: &str,
+    mtime: u64,
+    collection: &str,
+    config: &ConfigIngest,
+) -> Result<SyncCounts> {
+    let existing = scroll_point_payloads(collection, source_filter(path), &POSITION_FIELDS)
+        .await?
+        .into_iter()
+        .map(|(id, payload)| (point_id_to_string(&Some(id)), payload))
+        .collect::<HashMap<String, HashMap<String, Value>>>();
+
+    let mut counts = SyncCounts::default();
+    let mut current = HashSet::new();
+    // 同一文件中内容相同的 chunk 按出现次序生成不同的 id
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    let mut pending = vec![];
+    // 只有 mtime 变化的 point 合并为一次更新，位置变化的 point 按批一次请求更新
+    let mut mtime_changed = vec![];
+    let mut shifted = vec![];
+    for doc in read_documents(path, config)? {
+        for chunk in split(&doc.text, &config.splitter)? {
+            let hash = content_hash(&chunk.text);
+            let occurrence = occurrences.entry(hash.clone()).or_default();
+            let id = chunk_point_id(path, &hash, *occurrence);
+            *occurrence += 1;
+            current.insert(id.clone());
+
+            let mut position = serde_json::Map::new();
+            position.insert("chunk_index".to_string(), json!(chunk.index));
+            position.insert("mtime".to_string(), json!(mtime));
+            if let Some(heading) = chunk.heading {
+                position.insert("heading".to_string(), json!(heading));
+            }
+            if let Some(line) = doc.line {
+                position.insert("line".to_string(), json!(line));
+            }
+
+            if let Some(payload) = existing.get(&id) {
+                counts.skipped += 1;
+                let changed = changed_fields(payload, &position);
+                if changed.len() == 1 && changed.contains_key("mtime") {
+                    mtime_changed.push(point_id_from_string(&id));
+                } else if !changed.is_empty() {
+                    shifted.push((point_id_from_string(&id), Payload::from(changed)));
+                    if shifted.len() >= config.batch_size {
+                        set_payloads(collection, std::mem::take(&mut shifted)).await?;
+                    }
+                }
+                continue;
+            }
+            match existing.is_empty() {
+                true => counts.added += 1,
+                false => counts.updated += 1,
+            }
+
+            let mut payload = position;
+            payload.insert("path".to_string(), json!(path));
+            payload.insert("content_hash".to_string(), json!(hash));
+            payload.insert(config.text_field.clone(), json!(chunk.text));
+            pending.push((id, chunk.text, payload));
+            if pending.len() >= config.batch_size {
+                upsert_chunks(collection, std::mem::take(&mut pending)).await?;
+            }
+        }
+    }
+    if !pending.is_empty() {
+        upsert_chunks(collection, pending).await?;
+    }
+    if !shifted.is_empty() {
+        set_payloads(collection, shifted).await?;
+    }
+    if !mtime_changed.is_empty() {
+        let payload = Payload::try_from(json!({ "mtime": mtime }))?;
+        set_payload_by_ids(collection, payload, mtime_changed).await?;
+    }
+
+    let stale = existing
+        .keys()
+        .filter(|id| !current.contains(*id))
+        .map(|id| point_id_from_string(id))
+        .collect::<Vec<PointId>>();
+    counts.removed = stale.len();
+    if !stale.is_empty() {
+        delete_points_by_ids(collection, stale).await?;
+    }
+    Ok(counts)
+}
+
+// 一批 chunk 合并计算向量后写入
+async fn upsert_chunks(
+    collection: &str,
+    chunks: Vec<(String, String, serde_json::Map<String, serde_json::Value>)>,
+) -> Result<()> {
+    let texts = chunks
+        .iter()
+        .map(|(_, text, _)| text.clone())
+        .collect::<Vec<String>>();
+    let vectors = embedding_batch(&texts).await?;
+    if vectors.len() != chunks.len() {
+        return Err(anyhow!(
+            "{} embeddings for {} chunks",
+            vectors.len(),
+            chunks.len()
+        ));
+    }
+    let points = chunks
+        .into_iter()
+        .zip(vectors)
+        .map(|((id, _, payload), vector)| PointStruct::new(id, vector, Payload::from(payload)))
+        .collect::<Vec<PointStruct>>();
+    upsert_points(collection, points).await
+}
+
+/// 某个来源文件的全部 point
+pub(crate) fn source_filter(path: &str) -> Filter {
+    Filter::must([Condition::matches("path", path.to_string())])
+}
@@ -0,0 +1,93 @@
+use qdrant_client::qdrant::Value;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, ops::AddAssign};
+use uuid::Uuid;
+
+/// 增量入库的 chunk 计数。
+/// added 为新文档的 chunk，updated 为已入库文档中内容变化的 chunk，
+/// skipped 为内容未变化、未重新计算向量的 chunk，removed 为文档变化后删除的旧 chunk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncCounts {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub removed: usize,
+}
+
+impl AddAssign for SyncCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.added += other.added;
+        self.updated += other.updated;
+        self.skipped += other.skipped;
+        self.removed += other.removed;
+    }
+}
+
+/// chunk 文本的 sha256，记录在 payload 的 content_hash 中
+pub fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// 由来源 id 与内容 hash 生成确定的 point id，同一来源的相同内容重复入库时 id 不变。
+/// occurrence 为相同内容在该来源中第几次出现，从 0 开始
+pub fn chunk_point_id(source_id: &str, content_hash: &str, occurrence: usize) -> String {
+    let name = match occurrence {
+        0 => format!("{}\n{}", source_id, content_hash),
+        n => format!("{}\n{}\n{}", source_id, content_hash, n),
+    };
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()).to_string()
+}
+
+/// expected 中与已入库 payload 不一致的字段
+pub fn changed_fields(
+    existing: &HashMap<String, Value>,
+    expected: &serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Map<String, serde_json::Value> {
+    expected
+        .iter()
+        .filter(|(k, v)| existing.get(*k).cloned().map(Value::into_json).as_ref() != Some(*v))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{changed_fields, chunk_point_id, content_hash};
+    use qdrant_client::qdrant::Value;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    //cargo test ingest::ingest_sync::test::test_chunk_point_id -- --nocapture
+    #[test]
+    fn test_chunk_point_id() {
+        let hash = content_hash("hello");
+        assert_eq!(
+            hash,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        let id = chunk_point_id("docs/a.md", &hash, 0);
+        println!("{}", id);
+        assert_eq!(id, chunk_point_id("docs/a.md", &hash, 0));
+        assert_ne!(id, chunk_point_id("docs/b.md", &hash, 0));
+        assert_ne!(id, chunk_point_id("docs/a.md", &content_hash("hello!"), 0));
+        assert_ne!(id, chunk_point_id("docs/a.md", &hash, 1));
+    }
+
+    //cargo test ingest::ingest_sync::test::test_changed_fields -- --nocapture
+    #[test]
+    fn test_changed_fields() {
+        let mut existing = HashMap::new();
+        existing.insert("chunk_index".to_string(), Value::from(2i64));
+        existing.insert("mtime".to_string(), Value::from(100i64));
+        let expected = json!({"chunk_index": 2, "mtime": 100});
+        assert!(changed_fields(&existing, expected.as_object().unwrap()).is_empty());
+
+        let expected = json!({"chunk_index": 3, "mtime": 100, "line": 7});
+        let changed = changed_fields(&existing, expected.as_object().unwrap());
+        assert_eq!(
+            serde_json::Value::Object(changed),
+            json!({"chunk_index": 3, "line": 7})
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use super::ingest_folder::{
+    extension_allowed, prepare_collection, source_filter, source_path, sync_file, IngestFolder,
+};
+use crate::{
+    commons::{ModifyType, NotifyWatcher, RegexFilter},
+    configure::{config_watch::ConfigWatch, get_config},
+    resources::resource_qdrant::{
+        delete_points_by_filter, delete_points_by_ids, scroll_point_payloads,
+    },
+};
+use anyhow::Result;
+use qdrant_client::qdrant::{Condition, Filter, PointId, Value};
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+use tokio::{sync::mpsc, time::timeout};
+
+/// 监听目录并与向量库保持同步，新增、修改的文件重新切分入库，删除的文件按 path 删除对应 point。
+/// 事件按路径合并，静默 debounce_ms 后统一处理，连续写入只触发一次重建；
+/// 事件持续不断时，距第一个未处理事件 max_wait_ms 后也会处理
+pub async fn watch_folder(watch: ConfigWatch) -> Result<()> {
+    // 事件路径以监听目录为前缀，规范化后与其他入口入库的 path 一致
+    let watch = ConfigWatch {
+        folder: source_path(&watch.folder)?,
+        ..watch
+    };
+    let collection = get_config()?.qdrant.collection;
+    prepare_collection(&collection).await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let watcher = NotifyWatcher::new(&watch.folder)?;
+    // notify 的接收端是阻塞的，放到独立线程中
+    std::thread::spawn(move || watcher.watch_to_sender(tx));
+    log::info!("watching folder {}", watch.folder);
+    // 先整体同步一次，补上服务停止期间的新增及修改；监听已开始，同步期间的事件不会丢失
+    if let Err(e) = reindex_folder(&watch.folder, &watch).await {
+        log::error!("sync folder {} error: {}", watch.folder, e);
+    }
+
+    let debounce = Duration::from_millis(watch.debounce_ms);
+    let max_wait = Duration::from_millis(watch.max_wait_ms);
+    let mut pending: HashMap<String, ModifyType> = HashMap::new();
+    // 第一个未处理事件的时间
+    let mut first_event: Option<Instant> = None;
+    loop {
+        let received = match first_event {
+            None => rx.recv().await,
+            Some(first) => match next_wait(debounce, max_wait, first.elapsed()) {
+                Some(wait) => match timeout(wait, rx.recv()).await {
+                    Ok(r) => r,
+                    Err(_) => {
+                        sync_paths(std::mem::take(&mut pending), &watch, &collection).await;
+                        first_event = None;
+                        continue;
+                    }
+                },
+                None => {
+                    sync_paths(std::mem::take(&mut pending), &watch, &collection).await;
+                    first_event = None;
+                    continue;
+                }
+            },
+        };
+        match received {
+            Some(modified) => {
+                pending.insert(modified.path, modified.modify_type);
+                first_event.get_or_insert_with(Instant::now);
+            }
+            None => {
+                sync_paths(pending, &watch, &collection).await;
+                return Ok(());
+            }
+        }
+    }
+}
+
+// 下一个事件的最长等待时间，已达到 max_wait 时返回 None
+fn next_wait(debounce: Duration, max_wait: Duration, elapsed: Duration) -> Option<Duration> {
+    match max_wait.checked_sub(elapsed) {
+        Some(remaining) if !remaining.is_zero() => Some(debounce.min(remaining)),
+        _ => None,
+    }
+}
+
+async fn sync_paths(pending: HashMap<String, ModifyType>, watch: &ConfigWatch, collection: &str) {
+    for (path, modify_type) in pending {
+        log::info!("sync {:?} {}", modify_type, path);
+        // 以文件系统当前状态为准，事件类型仅作参考
+        let r = match Path::new(&path) {
+            p if p.is_file() => reindex_file(&path, watch, collection).await,
+            p if p.is_dir() => reindex_folder(&path, watch).await,
+            _ => remove_path(&path, collection).await,
+        };
+        if let Err(e) = r {
+            log::error!("sync {} error: {}", path, e);
+        }
+    }
+}
+
+async fn reindex_file(path: &str, watch: &ConfigWatch, collection: &str) -> Result<()> {
+    let config = get_config()?;
+    let regex_filter = RegexFilter::from_vec(&watch.exclude_regex, &watch.include_regex)?;
+    if !regex_filter.is_match(path) || !extension_allowed(path, &config.ingest.extensions) {
+        return Ok(());
+    }
+    let mtime = std::fs::metadata(path)?
+        .modified()?
+        .duration_since(UNIX_EPOCH)?
+        .as_secs();
+    let counts = sync_file(path, mtime, collection, &config.ingest).await?;
+    log::info!("reindex {}: {:?}", path, counts);
+    Ok(())
+}
+
+// 目录被创建或移入时整体入库
+async fn reindex_folder(path: &str, watch: &ConfigWatch) -> Result<()> {
+    let ingest = IngestFolder {
+        folder: path.to_string(),
+        include_regex: watch.include_regex.clone(),
+        exclude_regex: watch.exclude_regex.clone(),
+        last_modify_filter: None,
+    };
+    ingest.execute().await?;
+    Ok(())
+}
+
+// 删除该文件，或该目录下所有文件的 point。
+// 无前缀索引可用，先按子串粗筛再在本地按前缀过滤，避免误删 x/a/... 这类路径
+async fn remove_path(path: &str, collection: &str) -> Result<()> {
+    delete_points_by_filter(collection, source_filter(path)).await?;
+
+    let folder_prefix = format!("{}/", path.trim_end_matches('/'));
+    let candidates = scroll_point_payloads(
+        collection,
+        Filter::must([Condition::matches_text("path", folder_prefix.clone())]),
+        &["path"],
+    )
+    .await?;
+    let ids = under_folder(candidates, &folder_prefix);
+    if !ids.is_empty() {
+        delete_points_by_ids(collection, ids).await?;
+    }
+    Ok(())
+}
+
+fn under_folder(
+    points: Vec<(PointId, HashMap<String, Value>)>,
+    folder_prefix: &str,
+) -> Vec<PointId> {
+    points
+        .into_iter()
+        .filter(|(_, payload)| {
+            payload
+                .get("path")
+                .and_then(|v| v.as_str())
+                .is_some_and(|p| p.starts_with(folder_prefix))
+        })
+        .map(|(id, _)| id)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{next_wait, under_folder};
+    use qdrant_client::qdrant::{PointId, Value};
+    use std::{collections::HashMap, time::Duration};
+
+    //cargo test ingest::ingest_watch::test::test_next_wait -- --nocapture
+    #[test]
+    fn test_next_wait() {
+        let ms = Duration::from_millis;
+        assert_eq!(next_wait(ms(1000), ms(10000), ms(0)), Some(ms(1000)));
+        assert_eq!(next_wait(ms(1000), ms(10000), ms(9500)), Some(ms(500)));
+        assert_eq!(next_wait(ms(1000), ms(10000), ms(10000)), None);
+        assert_eq!(next_wait(ms(1000), ms(10000), ms(12000)), None);
+    }
+
+    //cargo test ingest::ingest_watch::test::test_under_folder -- --nocapture
+    #[test]
+    fn test_under_folder() {
+        let point = |id: u64, path: &str| {
+            let mut payload = HashMap::new();
+            payload.insert("path".to_string(), Value::from(path.to_string()));
+            (PointId::from(id), payload)
+        };
+        let points = vec![
+            point(1, "a/1.md"),
+            point(2, "x/a/2.md"),
+            point(3, "a/b/3.md"),
+            point(4, "ab/4.md"),
+        ];
+        let ids = under_folder(points, "a/");
+        assert_eq!(ids, vec![PointId::from(1), PointId::from(3)]);
+    }
+}
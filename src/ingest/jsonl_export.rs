@@ -0,0 +1,94 @@
+use crate::{
+    configure::get_config,
+    resources::resource_qdrant::{count_points, point_id_to_string, scroll_points},
+    tasks::TaskProgress,
+};
+use anyhow::Result;
+use qdrant_client::{qdrant::vector_output::Vector, Payload};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    sync::atomic::Ordering,
+};
+
+/// 导出 collection 为 jsonl，每行 {"id", "payload", "vector"}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJsonl {
+    // 输出文件，为空时写到标准输出
+    #[serde(default)]
+    pub file: Option<String>,
+    // 缺省为配置文件中的 collection
+    #[serde(default)]
+    pub collection: Option<String>,
+    #[serde(default)]
+    pub with_vectors: bool,
+    #[serde(default = "ExportJsonl::batch_size_default")]
+    pub batch_size: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportReport {
+    pub points: u64,
+}
+
+impl ExportJsonl {
+    fn batch_size_default() -> u32 {
+        256
+    }
+
+    /// 分页 scroll 并逐行写出，内存中只保留一页数据。导出总是从头开始，不使用断点
+    pub async fn execute_with_progress(&self, progress: &TaskProgress) -> Result<ExportReport> {
+        let collection = match &self.collection {
+            Some(c) => c.clone(),
+            None => get_config()?.qdrant.collection,
+        };
+        let writer: Box<dyn Write + Send> = match &self.file {
+            Some(f) => Box::new(File::create(f)?),
+            None => Box::new(io::stdout()),
+        };
+        let mut writer = BufWriter::new(writer);
+
+        progress
+            .total
+            .store(count_points(collection.as_str()).await?, Ordering::SeqCst);
+        let mut report = ExportReport::default();
+        let mut offset = None;
+        loop {
+            if progress.is_stopped() {
+                break;
+            }
+            let r = scroll_points(
+                collection.as_str(),
+                offset,
+                self.batch_size,
+                self.with_vectors,
+            )
+            .await?;
+            for p in r.result {
+                let mut line = json!({
+                    "id": point_id_to_string(&p.id),
+                    "payload": serde_json::Value::from(Payload::from(p.payload)),
+                });
+                if self.with_vectors {
+                    if let Some(Vector::Dense(d)) = p.vectors.as_ref().and_then(|v| v.get_vector())
+                    {
+                        line["vector"] = json!(d.data);
+                    }
+                }
+                serde_json::to_writer(&mut writer, &line)?;
+                writer.write_all(b"\n")?;
+                report.points += 1;
+                progress.processed.fetch_add(1, Ordering::SeqCst);
+            }
+            writer.flush()?;
+            match r.next_page_offset {
+                Some(next) => offset = Some(next),
+                None => break,
+            }
+        }
+        writer.flush()?;
+        Ok(report)
+    }
+}
@@ -0,0 +1,270 @@
+use super::{
+    ingest_folder::prepare_collection,
+    ingest_sync::{chunk_point_id, content_hash},
+};
+use crate::{
+    chunk::split,
+    commons::read_lines,
+    configure::{config_ingest::ConfigIngest, get_config},
+    embedding::embedding_batch,
+    resources::resource_qdrant::{point_id_from_string, point_id_to_string, upsert_points},
+    tasks::TaskProgress,
+};
+use anyhow::{anyhow, Result};
+use qdrant_client::{
+    qdrant::{PointId, PointStruct},
+    Payload,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{collections::HashMap, sync::atomic::Ordering};
+use uuid::Uuid;
+
+/// jsonl 批量导入参数，每行一个文档，整行作为 payload 写入
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportJsonl {
+    pub file: String,
+    // 缺省为 ingest.jsonl_text_field
+    #[serde(default)]
+    pub text_field: Option<String>,
+    // 缺省为 ingest.jsonl_id_field
+    #[serde(default)]
+    pub id_field: Option<String>,
+    // 缺省为配置文件中的 collection
+    #[serde(default)]
+    pub collection: Option<String>,
+    // 缺省为 ingest.batch_size
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub lines: usize,
+    // 写入的 chunk 数
+    pub imported: usize,
+    // 解析失败或所在批次写入失败的行数
+    pub failed: usize,
+    pub failed_batches: usize,
+}
+
+/// 一行文档切分后的一个 chunk
+struct ImportChunk {
+    id: PointId,
+    text: String,
+    payload: Value,
+}
+
+/// 待写入的一批 chunk 及其来源行数
+#[derive(Default)]
+struct ImportBatch {
+    chunks: Vec<ImportChunk>,
+    lines: usize,
+}
+
+impl ImportJsonl {
+    /// 逐行读取，每行按 ingest.splitter 切分，累计 batch_size 个 chunk 计算一次向量并写入；
+    /// 写入失败的批次记录日志后跳过。断点为已处理的行号
+    pub async fn execute_with_progress(&self, progress: &TaskProgress) -> Result<ImportReport> {
+        let config = get_config()?;
+        let text_field = self
+            .text_field
+            .clone()
+            .unwrap_or(config.ingest.jsonl_text_field.clone());
+        let id_field = self
+            .id_field
+            .clone()
+            .unwrap_or(config.ingest.jsonl_id_field.clone());
+        let collection = self
+            .collection
+            .clone()
+            .unwrap_or(config.qdrant.collection.clone());
+        let batch_size = self.batch_size.unwrap_or(config.ingest.batch_size).max(1);
+
+        progress
+            .total
+            .store(read_lines(&self.file)?.count() as u64, Ordering::SeqCst);
+        let start_line = progress
+            .checkpoint()
+            .and_then(|c| c.parse::<usize>().ok())
+            .map_or(0, |l| l + 1);
+        prepare_collection(&collection).await?;
+
+        let mut report = ImportReport::default();
+        let mut batch = ImportBatch::default();
+        // 未指定 id 的文档以文件为来源，相同内容的 chunk 按出现次序生成不同的 id
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        let mut last_line = None;
+        for (idx, line) in read_lines(&self.file)?.enumerate().skip(start_line) {
+            if progress.is_stopped() {
+                break;
+            }
+            report.lines += 1;
+            last_line = Some(idx);
+            let line = line?;
+            batch.lines += 1;
+            if !line.trim().is_empty() {
+                let parsed = self.parse_line(
+                    &line,
+                    idx,
+                    &text_field,
+                    &id_field,
+                    &config.ingest,
+                    &mut occurrences,
+                );
+                match parsed {
+                    Ok(chunks) => batch.chunks.extend(chunks),
+                    Err(e) => {
+                        log::warn!("{}:{} {}", self.file, idx + 1, e);
+                        batch.lines -= 1;
+                        report.failed += 1;
+                        progress.failed.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            }
+            if batch.chunks.len() >= batch_size {
+                self.write_batch(
+                    &collection,
+                    std::mem::take(&mut batch),
+                    &mut report,
+                    progress,
+                )
+                .await;
+                progress.set_checkpoint(idx.to_string());
+            }
+        }
+        if batch.lines > 0 {
+            self.write_batch(&collection, batch, &mut report, progress)
+                .await;
+        }
+        if let Some(idx) = last_line {
+            progress.set_checkpoint(idx.to_string());
+        }
+        log::info!("import {} finished: {:?}", self.file, report);
+        Ok(report)
+    }
+
+    async fn write_batch(
+        &self,
+        collection: &str,
+        batch: ImportBatch,
+        report: &mut ImportReport,
+        progress: &TaskProgress,
+    ) {
+        let count = batch.chunks.len();
+        if count > 0 {
+            if let Err(e) = import_batch(collection, batch.chunks).await {
+                log::error!(
+                    "import {} batch of {} lines error: {}",
+                    self.file,
+                    batch.lines,
+                    e
+                );
+                report.failed += batch.lines;
+                report.failed_batches += 1;
+                progress
+                    .failed
+                    .fetch_add(batch.lines as u64, Ordering::SeqCst);
+                return;
+            }
+        }
+        report.imported += count;
+        progress
+            .processed
+            .fetch_add(batch.lines as u64, Ordering::SeqCst);
+    }
+
+    // 整行作为每个 chunk 的 payload，文本字段替换为 chunk 文本。
+    // 只切出一个 chunk 时使用行中的 id，否则以该 id 为来源为每个 chunk 生成 id，并记录在 doc_id 中
+    fn parse_line(
+        &self,
+        line: &str,
+        line_index: usize,
+        text_field: &str,
+        id_field: &str,
+        config: &ConfigIngest,
+        occurrences: &mut HashMap<String, usize>,
+    ) -> Result<Vec<ImportChunk>> {
+        let doc: Value = serde_json::from_str(line)?;
+        if !doc.is_object() {
+            return Err(anyhow!("line is not a json object"));
+        }
+        let text = doc
+            .get(text_field)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("no text field {}", text_field))?;
+        let doc_id = match doc.get(id_field) {
+            Some(Value::Number(n)) => match n.as_u64() {
+                Some(n) => Some(PointId::from(n)),
+                None => return Err(anyhow!("id {} is not an unsigned integer", n)),
+            },
+            Some(Value::String(s)) => match Uuid::parse_str(s) {
+                Ok(uuid) => Some(PointId::from(uuid.to_string())),
+                // qdrant 只接受整数或 uuid，其他字符串 id 映射为确定的 uuid
+                Err(_) => Some(PointId::from(
+                    Uuid::new_v5(&Uuid::NAMESPACE_OID, s.as_bytes()).to_string(),
+                )),
+            },
+            Some(v) => return Err(anyhow!("unsupported id {}", v)),
+            None => None,
+        };
+
+        let pieces = split(text, &config.splitter)?;
+        let single = pieces.len() == 1;
+        let mut doc_occurrences = HashMap::new();
+        let mut chunks = vec![];
+        for chunk in pieces {
+            let hash = content_hash(&chunk.text);
+            let id = match &doc_id {
+                Some(id) if single => id.clone(),
+                Some(id) => {
+                    let source = point_id_to_string(&Some(id.clone()));
+                    let occurrence: &mut usize = doc_occurrences.entry(hash.clone()).or_default();
+                    let chunk_id = chunk_point_id(&source, &hash, *occurrence);
+                    *occurrence += 1;
+                    point_id_from_string(&chunk_id)
+                }
+                None => {
+                    let occurrence = occurrences.entry(hash.clone()).or_default();
+                    let chunk_id = chunk_point_id(&self.file, &hash, *occurrence);
+                    *occurrence += 1;
+                    point_id_from_string(&chunk_id)
+                }
+            };
+            let mut payload = doc.clone();
+            payload[&config.text_field] = json!(chunk.text);
+            payload["content_hash"] = json!(hash);
+            payload["chunk_index"] = json!(chunk.index);
+            payload["line"] = json!(line_index);
+            if let Some(heading) = &chunk.heading {
+                payload["heading"] = json!(heading);
+            }
+            if let (Some(id), false) = (&doc_id, single) {
+                payload["doc_id"] = json!(point_id_to_string(&Some(id.clone())));
+            }
+            chunks.push(ImportChunk {
+                id,
+                text: chunk.text,
+                payload,
+            });
+        }
+        Ok(chunks)
+    }
+}
+
+async fn import_batch(collection: &str, chunks: Vec<ImportChunk>) -> Result<()> {
+    let texts = chunks
+        .iter()
+        .map(|c| c.text.clone())
+        .collect::<Vec<String>>();
+    let vectors = embedding_batch(&texts).await?;
+    let mut points = vec![];
+    for (chunk, vector) in chunks.into_iter().zip(vectors) {
+        points.push(PointStruct::new(
+            chunk.id,
+            vector,
+            Payload::try_from(chunk.payload)?,
+        ));
+    }
+    upsert_points(collection, points).await
+}
@@ -0,0 +1,10 @@
+mod ingest_folder;
+mod ingest_sync;
+mod ingest_watch;
+mod jsonl_export;
+mod jsonl_import;
+
+pub use ingest_folder::*;
+pub use ingest_watch::*;
+pub use jsonl_export::*;
+pub use jsonl_import::*;
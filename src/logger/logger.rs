@@ -1,3 +1,5 @@
+use crate::configure::config_log::{ConfigLog, LogFormat, LogRotation};
+use anyhow::{anyhow, Result};
 use log::LevelFilter;
 use log4rs::append::console::ConsoleAppender;
 use log4rs::append::file::FileAppender;
@@ -8,10 +10,16 @@ use log4rs::append::rolling_file::RollingFileAppender;
 use log4rs::config::{Appender, Root};
 use log4rs::encode::pattern::PatternEncoder;
 use log4rs::Config;
-use tracing_appender::rolling::{self};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing_appender::rolling::Rotation;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{fmt, Layer};
+use tracing_subscriber::{fmt, reload, Layer, Registry};
+
+// 日志级别过滤层的句柄，配置热加载时调整 log.level
+static LEVEL_HANDLE: OnceLock<reload::Handle<tracing_subscriber::filter::LevelFilter, Registry>> =
+    OnceLock::new();
 
 pub fn init_log() {
     let window_size = 3; // log0, log1, log2
@@ -57,29 +65,87 @@ pub fn init_log() {
     let _ = log4rs::init_config(config).unwrap();
 }
 
-pub fn tracing_init() {
-    // 格式化输出层，并且输出到终端。
-    let formatting_layer = fmt::layer()
-        .pretty()
-        .with_file(true)
-        .with_line_number(true)
-        .with_writer(std::io::stdout)
-        .with_filter(tracing_subscriber::filter::LevelFilter::INFO)
-        .boxed();
+/// 按 log 配置初始化 tracing，log crate 的日志同样输出到 tracing
+pub fn tracing_init(config: &ConfigLog) -> Result<()> {
+    let level = tracing_subscriber::filter::LevelFilter::from_str(&config.level)
+        .map_err(|e| anyhow!("invalid log.level {}: {}", config.level, e))?;
+    let rotation = match config.rotation {
+        LogRotation::Minutely => Rotation::MINUTELY,
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+    let file_appender = tracing_appender::rolling::RollingFileAppender::new(
+        rotation,
+        &config.dir,
+        &config.file_prefix,
+    );
 
-    // 文件输出层
-    let file_appender = rolling::daily("logs/", "oss_pipe.log");
-    let file_layer = fmt::layer()
-        .with_ansi(false)
-        .with_file(true)
-        .with_line_number(true)
-        .with_writer(file_appender)
-        .with_filter(tracing_subscriber::filter::LevelFilter::INFO) // 文件输出日志等级
-        .boxed();
+    // 终端输出层及文件输出层
+    let (formatting_layer, file_layer) = match config.format {
+        LogFormat::Pretty => (
+            fmt::layer()
+                .pretty()
+                .with_file(true)
+                .with_line_number(true)
+                .with_writer(std::io::stdout)
+                .boxed(),
+            fmt::layer()
+                .with_ansi(false)
+                .with_file(true)
+                .with_line_number(true)
+                .with_writer(file_appender)
+                .boxed(),
+        ),
+        // json 格式每行一条，带当前 span 及全部上级 span 的字段，如 request_id
+        LogFormat::Json => (
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_file(true)
+                .with_line_number(true)
+                .with_writer(std::io::stdout)
+                .boxed(),
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_file(true)
+                .with_line_number(true)
+                .with_writer(file_appender)
+                .boxed(),
+        ),
+    };
 
-    let registry = tracing_subscriber::registry()
+    // 级别过滤对终端及文件输出同时生效
+    let (level_layer, handle) = reload::Layer::new(level);
+    tracing_subscriber::registry()
+        .with(level_layer)
         .with(file_layer)
-        .with(formatting_layer);
+        .with(formatting_layer)
+        .try_init()?;
+    let _ = LEVEL_HANDLE.set(handle);
+    Ok(())
+}
 
-    registry.init()
+/// 运行中调整日志级别，未初始化 tracing 时不做处理
+pub fn set_log_level(level: &str) -> Result<()> {
+    let level = tracing_subscriber::filter::LevelFilter::from_str(level)
+        .map_err(|e| anyhow!("invalid log.level {}: {}", level, e))?;
+    let handle = match LEVEL_HANDLE.get() {
+        Some(h) => h,
+        None => return Ok(()),
+    };
+    handle.reload(level)?;
+    // log crate 的日志经 LogTracer 转发，其最大级别在初始化时按原级别设置
+    log::set_max_level(match level.into_level() {
+        None => LevelFilter::Off,
+        Some(tracing::Level::ERROR) => LevelFilter::Error,
+        Some(tracing::Level::WARN) => LevelFilter::Warn,
+        Some(tracing::Level::INFO) => LevelFilter::Info,
+        Some(tracing::Level::DEBUG) => LevelFilter::Debug,
+        Some(tracing::Level::TRACE) => LevelFilter::Trace,
+    });
+    Ok(())
 }
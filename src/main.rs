@@ -1,14 +1,19 @@
-use logger::{init_log, tracing_init};
+mod chunk;
 mod cmd;
 mod commons;
 mod configure;
+mod daemon;
 mod embedding;
+mod evaluate;
 mod httpserver;
+mod ingest;
 mod logger;
+mod metrics;
 mod resources;
+mod tasks;
 
 fn main() {
     // init_log();
-    tracing_init();
+    // 日志按配置初始化，见 cmd_match
     cmd::run_app();
 }
@@ -0,0 +1,4 @@
+pub mod registry;
+mod server_metrics;
+
+pub use server_metrics::*;
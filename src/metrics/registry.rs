@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// 单个指标，按 Prometheus 文本格式输出
+pub trait Metric: Send + Sync {
+    fn kind() -> &'static str
+    where
+        Self: Sized;
+    fn render(&self, name: &str, labels: &str, out: &mut String);
+}
+
+#[derive(Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+    pub fn inc_by(&self, v: u64) {
+        self.0.fetch_add(v, Ordering::Relaxed);
+    }
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Metric for Counter {
+    fn kind() -> &'static str {
+        "counter"
+    }
+    fn render(&self, name: &str, labels: &str, out: &mut String) {
+        let _ = writeln!(out, "{}{} {}", name, braced(labels), self.get());
+    }
+}
+
+#[derive(Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+    pub fn set(&self, v: i64) {
+        self.0.store(v, Ordering::Relaxed);
+    }
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Metric for Gauge {
+    fn kind() -> &'static str {
+        "gauge"
+    }
+    fn render(&self, name: &str, labels: &str, out: &mut String) {
+        let _ = writeln!(out, "{}{} {}", name, braced(labels), self.get());
+    }
+}
+
+pub struct Histogram {
+    bounds: &'static [f64],
+    // 各桶非累计计数，输出时再累加
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    // f64 的位表示
+    sum: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    pub fn observe(&self, v: f64) {
+        if let Some(idx) = self.bounds.iter().position(|b| v <= *b) {
+            self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .sum
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((f64::from_bits(bits) + v).to_bits())
+            });
+    }
+}
+
+impl Metric for Histogram {
+    fn kind() -> &'static str {
+        "histogram"
+    }
+    fn render(&self, name: &str, labels: &str, out: &mut String) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0;
+        for (bound, bucket) in self.bounds.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}{}le=\"{}\"}} {}",
+                name, labels, sep, bound, cumulative
+            );
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}{}le=\"+Inf\"}} {}",
+            name, labels, sep, count
+        );
+        let sum = f64::from_bits(self.sum.load(Ordering::Relaxed));
+        let _ = writeln!(out, "{}_sum{} {}", name, braced(labels), sum);
+        let _ = writeln!(out, "{}_count{} {}", name, braced(labels), count);
+    }
+}
+
+/// 同名指标按标签值区分，无标签的指标用 with(&[]) 取值
+pub struct Family<M: Metric> {
+    name: &'static str,
+    help: &'static str,
+    label_names: &'static [&'static str],
+    new_metric: fn() -> M,
+    metrics: RwLock<BTreeMap<Vec<String>, Arc<M>>>,
+}
+
+impl<M: Metric> Family<M> {
+    pub fn new(
+        name: &'static str,
+        help: &'static str,
+        label_names: &'static [&'static str],
+        new_metric: fn() -> M,
+    ) -> Self {
+        Self {
+            name,
+            help,
+            label_names,
+            new_metric,
+            metrics: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn with(&self, label_values: &[&str]) -> Arc<M> {
+        let key = label_values
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>();
+        if let Some(m) = self.metrics.read().ok().and_then(|m| m.get(&key).cloned()) {
+            return m;
+        }
+        let mut metrics = match self.metrics.write() {
+            Ok(m) => m,
+            Err(e) => e.into_inner(),
+        };
+        Arc::clone(
+            metrics
+                .entry(key)
+                .or_insert_with(|| Arc::new((self.new_metric)())),
+        )
+    }
+
+    pub fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", self.name, M::kind());
+        let metrics = match self.metrics.read() {
+            Ok(m) => m,
+            Err(e) => e.into_inner(),
+        };
+        for (values, metric) in metrics.iter() {
+            let labels = self
+                .label_names
+                .iter()
+                .zip(values)
+                .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+                .collect::<Vec<String>>()
+                .join(",");
+            metric.render(self.name, &labels, out);
+        }
+    }
+}
+
+fn braced(labels: &str) -> String {
+    match labels.is_empty() {
+        true => String::new(),
+        false => format!("{{{}}}", labels),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Counter, Family, Histogram};
+
+    static BOUNDS: [f64; 2] = [0.1, 1.0];
+
+    //cargo test metrics::registry::test::test_render -- --nocapture
+    #[test]
+    fn test_render() {
+        let requests = Family::<Counter>::new(
+            "requests_total",
+            "requests",
+            &["route", "status"],
+            Counter::default,
+        );
+        requests.with(&["/health", "200"]).inc();
+        requests.with(&["/health", "200"]).inc();
+        requests.with(&["/a\"b", "503"]).inc();
+        let latency = Family::new("latency_seconds", "latency", &[], || {
+            Histogram::new(&BOUNDS)
+        });
+        latency.with(&[]).observe(0.05);
+        latency.with(&[]).observe(0.5);
+        latency.with(&[]).observe(5.0);
+
+        let mut out = String::new();
+        requests.render(&mut out);
+        latency.render(&mut out);
+        println!("{}", out);
+        assert!(out.contains("# TYPE requests_total counter"));
+        assert!(out.contains("requests_total{route=\"/health\",status=\"200\"} 2"));
+        assert!(out.contains("requests_total{route=\"/a\\\"b\",status=\"503\"} 1"));
+        assert!(out.contains("latency_seconds_bucket{le=\"0.1\"} 1"));
+        assert!(out.contains("latency_seconds_bucket{le=\"1\"} 2"));
+        assert!(out.contains("latency_seconds_bucket{le=\"+Inf\"} 3"));
+        assert!(out.contains("latency_seconds_sum 5.55"));
+        assert!(out.contains("latency_seconds_count 3"));
+    }
+}
@@ -0,0 +1,184 @@
+use super::registry::{Counter, Family, Gauge, Histogram};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use std::time::Instant;
+
+static LATENCY_BUCKETS: [f64; 14] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+static BATCH_SIZE_BUCKETS: [f64; 10] = [1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0];
+
+pub static GLOBAL_METRICS: Lazy<ServerMetrics> = Lazy::new(ServerMetrics::new);
+
+pub struct ServerMetrics {
+    pub http_requests: Family<Counter>,
+    pub http_request_duration: Family<Histogram>,
+    pub http_in_flight: Family<Gauge>,
+    pub embedding_tokens: Family<Counter>,
+    pub embedding_batch_size: Family<Histogram>,
+    pub generation_tokens: Family<Counter>,
+    pub model_queue_depth: Family<Gauge>,
+    pub model_forward_duration: Family<Histogram>,
+    pub qdrant_request_duration: Family<Histogram>,
+    pub qdrant_errors: Family<Counter>,
+    pub process_resident_memory: Family<Gauge>,
+    pub process_virtual_memory: Family<Gauge>,
+}
+
+impl ServerMetrics {
+    fn new() -> Self {
+        Self {
+            http_requests: Family::new(
+                "http_requests_total",
+                "HTTP requests by method, route and status",
+                &["method", "route", "status"],
+                Counter::default,
+            ),
+            http_request_duration: Family::new(
+                "http_request_duration_seconds",
+                "HTTP request latency by method and route",
+                &["method", "route"],
+                latency_histogram,
+            ),
+            http_in_flight: Family::new(
+                "http_requests_in_flight",
+                "HTTP requests being processed",
+                &[],
+                Gauge::default,
+            ),
+            embedding_tokens: Family::new(
+                "embedding_tokens_total",
+                "Tokens passed through the embedding model",
+                &[],
+                Counter::default,
+            ),
+            embedding_batch_size: Family::new(
+                "embedding_batch_size",
+                "Texts per embedding batch",
+                &[],
+                || Histogram::new(&BATCH_SIZE_BUCKETS),
+            ),
+            generation_tokens: Family::new(
+                "generation_tokens_total",
+                "Tokens generated by the generation model",
+                &[],
+                Counter::default,
+            ),
+            model_queue_depth: Family::new(
+                "model_queue_depth",
+                "Calls waiting for or running on a model",
+                &["model"],
+                Gauge::default,
+            ),
+            model_forward_duration: Family::new(
+                "model_forward_duration_seconds",
+                "Model forward pass latency",
+                &["model"],
+                latency_histogram,
+            ),
+            qdrant_request_duration: Family::new(
+                "qdrant_request_duration_seconds",
+                "Qdrant call latency by operation",
+                &["operation"],
+                latency_histogram,
+            ),
+            qdrant_errors: Family::new(
+                "qdrant_errors_total",
+                "Failed Qdrant calls by operation",
+                &["operation"],
+                Counter::default,
+            ),
+            process_resident_memory: Family::new(
+                "process_resident_memory_bytes",
+                "Resident memory size in bytes",
+                &[],
+                Gauge::default,
+            ),
+            process_virtual_memory: Family::new(
+                "process_virtual_memory_bytes",
+                "Virtual memory size in bytes",
+                &[],
+                Gauge::default,
+            ),
+        }
+    }
+
+    /// Prometheus 文本格式，进程内存在输出时读取
+    pub fn render(&self) -> String {
+        if let Some((rss, vsz)) = process_memory() {
+            self.process_resident_memory.with(&[]).set(rss);
+            self.process_virtual_memory.with(&[]).set(vsz);
+        }
+        let mut out = String::new();
+        self.http_requests.render(&mut out);
+        self.http_request_duration.render(&mut out);
+        self.http_in_flight.render(&mut out);
+        self.embedding_tokens.render(&mut out);
+        self.embedding_batch_size.render(&mut out);
+        self.generation_tokens.render(&mut out);
+        self.model_queue_depth.render(&mut out);
+        self.model_forward_duration.render(&mut out);
+        self.qdrant_request_duration.render(&mut out);
+        self.qdrant_errors.render(&mut out);
+        self.process_resident_memory.render(&mut out);
+        self.process_virtual_memory.render(&mut out);
+        out
+    }
+
+    pub fn observe_forward(&self, model: &str, start: Instant) {
+        self.model_forward_duration
+            .with(&[model])
+            .observe(start.elapsed().as_secs_f64());
+    }
+
+    pub fn observe_qdrant<T, E>(
+        &self,
+        operation: &str,
+        start: Instant,
+        result: &std::result::Result<T, E>,
+    ) {
+        self.qdrant_request_duration
+            .with(&[operation])
+            .observe(start.elapsed().as_secs_f64());
+        if result.is_err() {
+            self.qdrant_errors.with(&[operation]).inc();
+        }
+    }
+
+    /// 进入模型调用时队列深度加一，返回值 drop 时减一
+    pub fn enter_model(&self, model: &str) -> QueueGuard {
+        let gauge = self.model_queue_depth.with(&[model]);
+        gauge.inc();
+        QueueGuard(gauge)
+    }
+
+    /// 处理中的 http 请求数加一，返回值 drop 时减一，连接断开导致请求被取消时同样减一
+    pub fn enter_http(&self) -> QueueGuard {
+        let gauge = self.http_in_flight.with(&[]);
+        gauge.inc();
+        QueueGuard(gauge)
+    }
+}
+
+pub struct QueueGuard(Arc<Gauge>);
+
+impl Drop for QueueGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+fn latency_histogram() -> Histogram {
+    Histogram::new(&LATENCY_BUCKETS)
+}
+
+// 读取 /proc/self/status 中的 VmRSS、VmSize，非 Linux 系统返回 None
+fn process_memory() -> Option<(i64, i64)> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let field = |name: &str| -> Option<i64> {
+        let line = status.lines().find(|l| l.starts_with(name))?;
+        let kb = line.split_whitespace().nth(1)?.parse::<i64>().ok()?;
+        Some(kb * 1024)
+    };
+    Some((field("VmRSS:")?, field("VmSize:")?))
+}
@@ -1,30 +1,76 @@
-use crate::configure::get_config;
-use anyhow::Result;
+use crate::configure::{config_qdrant::ConfigQdrant, get_config};
+use crate::metrics::GLOBAL_METRICS;
+use anyhow::{anyhow, Result};
 use once_cell::sync::Lazy;
 use qdrant_client::{
-    qdrant::{SearchPointsBuilder, SearchResponse},
-    Qdrant,
+    qdrant::{
+        point_id::PointIdOptions, points_update_operation, vectors_config, CountPointsBuilder,
+        CreateCollectionBuilder, DeletePointsBuilder, Distance, Filter, PayloadIncludeSelector,
+        PointId, PointStruct, PointsIdsList, PointsSelector, PointsUpdateOperation,
+        ScrollPointsBuilder, ScrollResponse, SearchPointsBuilder, SearchResponse,
+        SetPayloadPointsBuilder, UpdateBatchPointsBuilder, UpsertPointsBuilder, Value,
+        VectorParamsBuilder,
+    },
+    Payload, Qdrant, QdrantError,
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
-use std::{sync::Arc, time::Duration};
 
-pub static GLOBAL_QDRANT: Lazy<Arc<Qdrant>> = Lazy::new(|| {
+// 配置热加载时整体替换，使用方通过 qdrant_client() 取当前的 client
+pub static GLOBAL_QDRANT: Lazy<RwLock<Arc<Qdrant>>> = Lazy::new(|| {
     let config = get_config().unwrap().qdrant;
+    let client = match new_qdrant_client(&config) {
+        Ok(q) => q,
+        Err(err) => panic!("{}", err),
+    };
+    RwLock::new(Arc::new(client))
+});
+
+pub fn qdrant_client() -> Arc<Qdrant> {
+    match GLOBAL_QDRANT.read() {
+        Ok(c) => Arc::clone(&c),
+        Err(e) => Arc::clone(&e.into_inner()),
+    }
+}
+
+/// 以新配置重建 client，已在执行的请求继续使用旧 client
+pub fn rebuild_qdrant_client(config: &ConfigQdrant) -> Result<()> {
+    let client = Arc::new(new_qdrant_client(config)?);
+    match GLOBAL_QDRANT.write() {
+        Ok(mut c) => *c = client,
+        Err(e) => *e.into_inner() = client,
+    }
+    Ok(())
+}
+
+pub fn new_qdrant_client(config: &ConfigQdrant) -> Result<Qdrant> {
     let mut q_config = Qdrant::from_url(&config.uri)
         .timeout(Duration::from_secs(config.timeout))
         .connect_timeout(Duration::from_secs(config.connect_timeout))
-        .api_key(config.api_key);
+        .api_key(config.api_key.clone());
     if config.keep_alive_while_idle {
         q_config = q_config.keep_alive_while_idle();
     }
-    let client = match q_config.build() {
-        Ok(q) => q,
-        Err(err) => panic!("{}", err),
-    };
-    Arc::new(client)
-});
+    Ok(q_config.build()?)
+}
+
+// 记录每次 qdrant 调用的耗时及错误数
+async fn observed<T>(
+    operation: &str,
+    request: impl Future<Output = std::result::Result<T, QdrantError>>,
+) -> std::result::Result<T, QdrantError> {
+    let start = Instant::now();
+    let result = request.await;
+    GLOBAL_METRICS.observe_qdrant(operation, start, &result);
+    result
+}
 
 pub async fn health_check() -> Result<()> {
-    let _ = GLOBAL_QDRANT.health_check().await?;
+    let _ = observed("health_check", qdrant_client().health_check()).await?;
     Ok(())
 }
 
@@ -32,9 +78,234 @@ pub async fn search_points(
     collection_name: impl Into<String>,
     vector: impl Into<Vec<f32>>,
     limit: u64,
+    with_vectors: bool,
+    filter: Option<Filter>,
+    score_threshold: Option<f32>,
 ) -> Result<SearchResponse> {
-    let search_result = GLOBAL_QDRANT
-        .search_points(SearchPointsBuilder::new(collection_name, vector, limit).with_payload(true))
-        .await?;
+    let mut builder = SearchPointsBuilder::new(collection_name, vector, limit)
+        .with_payload(true)
+        .with_vectors(with_vectors);
+    if let Some(f) = filter {
+        builder = builder.filter(f);
+    }
+    if let Some(t) = score_threshold {
+        builder = builder.score_threshold(t);
+    }
+    let search_result = observed("search_points", qdrant_client().search_points(builder)).await?;
     Ok(search_result)
 }
+
+/// collection 的向量维度，collection 不存在时返回 None，多向量 collection 返回错误
+pub async fn collection_dimension(client: &Qdrant, collection_name: &str) -> Result<Option<u64>> {
+    if !observed(
+        "collection_exists",
+        client.collection_exists(collection_name),
+    )
+    .await?
+    {
+        return Ok(None);
+    }
+    let info = observed("collection_info", client.collection_info(collection_name)).await?;
+    let vectors = info
+        .result
+        .and_then(|r| r.config)
+        .and_then(|c| c.params)
+        .and_then(|p| p.vectors_config)
+        .and_then(|v| v.config);
+    match vectors {
+        Some(vectors_config::Config::Params(p)) => Ok(Some(p.size)),
+        _ => Err(anyhow!(
+            "collection {} has no single unnamed vector",
+            collection_name
+        )),
+    }
+}
+
+/// collection 不存在时按向量维度创建，距离使用 Cosine
+pub async fn ensure_collection(collection_name: &str, dimension: u64) -> Result<()> {
+    let client = qdrant_client();
+    if observed(
+        "collection_exists",
+        client.collection_exists(collection_name),
+    )
+    .await?
+    {
+        return Ok(());
+    }
+    observed(
+        "create_collection",
+        client.create_collection(
+            CreateCollectionBuilder::new(collection_name)
+                .vectors_config(VectorParamsBuilder::new(dimension, Distance::Cosine)),
+        ),
+    )
+    .await?;
+    log::info!("collection {} created", collection_name);
+    Ok(())
+}
+
+pub async fn upsert_points(
+    collection_name: impl Into<String>,
+    points: Vec<PointStruct>,
+) -> Result<()> {
+    observed(
+        "upsert_points",
+        qdrant_client().upsert_points(UpsertPointsBuilder::new(collection_name, points).wait(true)),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_points_by_filter(
+    collection_name: impl Into<String>,
+    filter: Filter,
+) -> Result<()> {
+    observed(
+        "delete_points",
+        qdrant_client().delete_points(
+            DeletePointsBuilder::new(collection_name)
+                .points(filter)
+                .wait(true),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_points_by_ids(
+    collection_name: impl Into<String>,
+    ids: Vec<PointId>,
+) -> Result<()> {
+    observed(
+        "delete_points",
+        qdrant_client().delete_points(
+            DeletePointsBuilder::new(collection_name)
+                .points(PointsIdsList { ids })
+                .wait(true),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 覆盖指定 point 的部分 payload 字段，其余字段不变
+pub async fn set_payload_by_ids(
+    collection_name: impl Into<String>,
+    payload: Payload,
+    ids: Vec<PointId>,
+) -> Result<()> {
+    observed(
+        "set_payload",
+        qdrant_client().set_payload(
+            SetPayloadPointsBuilder::new(collection_name, payload)
+                .points_selector(PointsIdsList { ids })
+                .wait(true),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 为每个 point 覆盖各自的部分 payload 字段，合并为一次批量请求
+pub async fn set_payloads(
+    collection_name: impl Into<String>,
+    updates: Vec<(PointId, Payload)>,
+) -> Result<()> {
+    let operations = updates
+        .into_iter()
+        .map(|(id, payload)| PointsUpdateOperation {
+            operation: Some(points_update_operation::Operation::SetPayload(
+                points_update_operation::SetPayload {
+                    payload: payload.into(),
+                    points_selector: Some(PointsSelector::from(vec![id])),
+                    shard_key_selector: None,
+                    key: None,
+                },
+            )),
+        })
+        .collect::<Vec<PointsUpdateOperation>>();
+    observed(
+        "update_points_batch",
+        qdrant_client().update_points_batch(
+            UpdateBatchPointsBuilder::new(collection_name, operations).wait(true),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 返回满足过滤条件的全部 point 及其 fields 中的 payload 字段
+pub async fn scroll_point_payloads(
+    collection_name: &str,
+    filter: Filter,
+    fields: &[&str],
+) -> Result<Vec<(PointId, HashMap<String, Value>)>> {
+    let selector = PayloadIncludeSelector {
+        fields: fields.iter().map(|f| f.to_string()).collect(),
+    };
+    let mut points = vec![];
+    let mut offset: Option<PointId> = None;
+    loop {
+        let mut builder = ScrollPointsBuilder::new(collection_name)
+            .filter(filter.clone())
+            .limit(1000)
+            .with_payload(selector.clone())
+            .with_vectors(false);
+        if let Some(o) = offset {
+            builder = builder.offset(o);
+        }
+        let r = observed("scroll", qdrant_client().scroll(builder)).await?;
+        points.extend(
+            r.result
+                .into_iter()
+                .filter_map(|p| p.id.map(|id| (id, p.payload))),
+        );
+        match r.next_page_offset {
+            Some(next) => offset = Some(next),
+            None => break,
+        }
+    }
+    Ok(points)
+}
+
+pub async fn scroll_points(
+    collection_name: impl Into<String>,
+    offset: Option<PointId>,
+    limit: u32,
+    with_vectors: bool,
+) -> Result<ScrollResponse> {
+    let mut builder = ScrollPointsBuilder::new(collection_name)
+        .limit(limit)
+        .with_payload(true)
+        .with_vectors(with_vectors);
+    if let Some(o) = offset {
+        builder = builder.offset(o);
+    }
+    let r = observed("scroll", qdrant_client().scroll(builder)).await?;
+    Ok(r)
+}
+
+pub async fn count_points(collection_name: impl Into<String>) -> Result<u64> {
+    let r = observed(
+        "count",
+        qdrant_client().count(CountPointsBuilder::new(collection_name).exact(true)),
+    )
+    .await?;
+    Ok(r.result.map_or(0, |c| c.count))
+}
+
+pub fn point_id_to_string(point_id: &Option<PointId>) -> String {
+    match point_id.as_ref().and_then(|p| p.point_id_options.as_ref()) {
+        Some(PointIdOptions::Num(n)) => n.to_string(),
+        Some(PointIdOptions::Uuid(s)) => s.clone(),
+        None => "".to_string(),
+    }
+}
+
+// 数字 id 与 uuid 的字符串形式还原为 PointId
+pub fn point_id_from_string(id: &str) -> PointId {
+    match id.parse::<u64>() {
+        Ok(n) => PointId::from(n),
+        Err(_) => PointId::from(id.to_string()),
+    }
+}
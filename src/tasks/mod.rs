@@ -0,0 +1,6 @@
+mod task;
+mod task_manager;
+mod task_reembed;
+
+pub use task::*;
+pub use task_manager::*;
@@ -0,0 +1,157 @@
+use super::task_reembed::TaskReembed;
+use crate::{
+    commons::struct_to_json_string,
+    ingest::{ExportJsonl, ImportJsonl, IngestFolder},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// 任务类型及参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TaskType {
+    /// 目录入库
+    Ingest(IngestFolder),
+    /// jsonl 批量导入
+    Import(ImportJsonl),
+    /// 导出 collection 为 jsonl
+    Export(ExportJsonl),
+    /// 使用当前模型重新计算 collection 中所有 point 的向量
+    Reembed(TaskReembed),
+}
+
+impl TaskType {
+    /// 任务指定的 collection，为空时使用配置文件中的 collection
+    pub fn collection(&self) -> Option<&str> {
+        match self {
+            TaskType::Ingest(_) => None,
+            TaskType::Import(import) => import.collection.as_deref(),
+            TaskType::Export(export) => export.collection.as_deref(),
+            TaskType::Reembed(reembed) => reembed.collection.as_deref(),
+        }
+    }
+
+    /// 执行任务，返回任务摘要
+    pub async fn execute(&self, progress: &TaskProgress) -> Result<String> {
+        let summary = match self {
+            TaskType::Ingest(ingest) => {
+                let report = ingest.execute_with_progress(progress).await?;
+                struct_to_json_string(&report)?
+            }
+            TaskType::Import(import) => {
+                let report = import.execute_with_progress(progress).await?;
+                struct_to_json_string(&report)?
+            }
+            TaskType::Export(export) => {
+                let report = export.execute_with_progress(progress).await?;
+                struct_to_json_string(&report)?
+            }
+            TaskType::Reembed(reembed) => {
+                let count = reembed.execute(progress).await?;
+                format!("{} points reembedded", count)
+            }
+        };
+        Ok(summary)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Created,
+    Running,
+    Stopped,
+    Finished,
+    Failed,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProgressSnapshot {
+    pub total: u64,
+    pub processed: u64,
+    pub failed: u64,
+    // 断点，任务重新启动时由此继续
+    #[serde(default)]
+    pub checkpoint: Option<String>,
+}
+
+/// 运行中任务的进度计数及停止标记
+#[derive(Debug, Default)]
+pub struct TaskProgress {
+    stop_mark: AtomicBool,
+    pub total: AtomicU64,
+    pub processed: AtomicU64,
+    pub failed: AtomicU64,
+    checkpoint: Mutex<Option<String>>,
+}
+
+impl TaskProgress {
+    pub fn from_snapshot(snapshot: &ProgressSnapshot) -> Self {
+        Self {
+            stop_mark: AtomicBool::new(false),
+            total: AtomicU64::new(snapshot.total),
+            processed: AtomicU64::new(snapshot.processed),
+            failed: AtomicU64::new(snapshot.failed),
+            checkpoint: Mutex::new(snapshot.checkpoint.clone()),
+        }
+    }
+
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        ProgressSnapshot {
+            total: self.total.load(Ordering::SeqCst),
+            processed: self.processed.load(Ordering::SeqCst),
+            failed: self.failed.load(Ordering::SeqCst),
+            checkpoint: self.checkpoint(),
+        }
+    }
+
+    pub fn stop(&self) {
+        self.stop_mark.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop_mark.load(Ordering::SeqCst)
+    }
+
+    pub fn checkpoint(&self) -> Option<String> {
+        match self.checkpoint.lock() {
+            Ok(c) => c.clone(),
+            Err(_) => None,
+        }
+    }
+
+    pub fn set_checkpoint(&self, checkpoint: String) {
+        if let Ok(mut c) = self.checkpoint.lock() {
+            *c = Some(checkpoint);
+        }
+    }
+}
+
+/// 持久化的任务描述及状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskMeta {
+    pub task_id: String,
+    pub name: String,
+    pub task: TaskType,
+    pub status: TaskStatus,
+    #[serde(default)]
+    pub progress: ProgressSnapshot,
+    // 任务摘要或错误信息
+    #[serde(default)]
+    pub message: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
@@ -0,0 +1,431 @@
+use super::task::{now_secs, ProgressSnapshot, TaskMeta, TaskProgress, TaskStatus, TaskType};
+use crate::{
+    commons::{read_yaml_file, struct_to_yml_file},
+    configure::get_config,
+    embedding::GLOBAL_RUNTIME,
+};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+/// 可由调用方处理的任务错误，http 接口据此返回 404 或 400
+#[derive(Debug)]
+pub enum TaskError {
+    NotExist(String),
+    Running(String),
+    NotRunning(String),
+    Invalid(String),
+}
+
+impl std::error::Error for TaskError {}
+
+impl Display for TaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskError::NotExist(id) => write!(f, "task {} not exist", id),
+            TaskError::Running(id) => write!(f, "task {} is running", id),
+            TaskError::NotRunning(id) => write!(f, "task {} is not running", id),
+            TaskError::Invalid(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+pub static GLOBAL_TASK_MANAGER: Lazy<TaskManager> = Lazy::new(TaskManager::new);
+
+/// 管理任务的创建、启停及状态持久化，每个任务的状态以 yaml 保存在 task.meta_dir 下。
+/// 需要同时持有两把锁时一律先 tasks 后 living，只读查询复制数据后立即释放锁
+/// 写 meta 文件在释放锁之后进行，不阻塞查询
+pub struct TaskManager {
+    tasks: RwLock<HashMap<String, TaskMeta>>,
+    // 运行中任务的进度
+    living: RwLock<HashMap<String, Arc<TaskProgress>>>,
+    // 未设置时使用配置中的 task.meta_dir
+    meta_dir: Option<PathBuf>,
+}
+
+impl TaskManager {
+    fn new() -> Self {
+        Self {
+            tasks: RwLock::new(HashMap::new()),
+            living: RwLock::new(HashMap::new()),
+            meta_dir: None,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_meta_dir(meta_dir: PathBuf) -> Self {
+        Self {
+            meta_dir: Some(meta_dir),
+            ..Self::new()
+        }
+    }
+
+    /// 从 meta_dir 恢复任务，上次退出时仍在运行的任务置为 stopped，可通过 start 从断点继续
+    pub fn load(&self) -> Result<()> {
+        let meta_dir = self.meta_dir()?;
+        if !meta_dir.exists() {
+            return Ok(());
+        }
+        let mut tasks = self.tasks.write().map_err(|e| anyhow!(e.to_string()))?;
+        for entry in fs::read_dir(&meta_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("yml") {
+                continue;
+            }
+            let mut meta = match read_yaml_file::<TaskMeta>(&path.display().to_string()) {
+                Ok(m) => m,
+                Err(e) => {
+                    log::error!("load task meta {} error: {}", path.display(), e);
+                    continue;
+                }
+            };
+            if meta.status == TaskStatus::Running {
+                meta.status = TaskStatus::Stopped;
+                meta.message = Some("interrupted by server restart".to_string());
+                self.save_meta(&meta)?;
+            }
+            tasks.insert(meta.task_id.clone(), meta);
+        }
+        log::info!("{} tasks loaded from {}", tasks.len(), meta_dir.display());
+        Ok(())
+    }
+
+    pub fn create(&self, name: String, task: TaskType) -> Result<String> {
+        // 后台任务不能写标准输出
+        if let TaskType::Export(export) = &task {
+            if export.file.is_none() {
+                return Err(
+                    TaskError::Invalid("export task requires an output file".to_string()).into(),
+                );
+            }
+        }
+        let now = now_secs();
+        let meta = TaskMeta {
+            task_id: Uuid::new_v4().to_string(),
+            name,
+            task,
+            status: TaskStatus::Created,
+            progress: ProgressSnapshot::default(),
+            message: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.save_meta(&meta)?;
+        let task_id = meta.task_id.clone();
+        self.tasks
+            .write()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .insert(task_id.clone(), meta);
+        Ok(task_id)
+    }
+
+    pub fn start(&'static self, task_id: &str) -> Result<()> {
+        let mut tasks = self.tasks.write().map_err(|e| anyhow!(e.to_string()))?;
+        let meta = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| TaskError::NotExist(task_id.to_string()))?;
+        let mut living = self.living.write().map_err(|e| anyhow!(e.to_string()))?;
+        if living.contains_key(task_id) {
+            return Err(TaskError::Running(task_id.to_string()).into());
+        }
+
+        // 已结束的任务重新执行，中断的任务从断点继续
+        if meta.status == TaskStatus::Finished || meta.status == TaskStatus::Failed {
+            meta.progress = ProgressSnapshot::default();
+        }
+        let progress = Arc::new(TaskProgress::from_snapshot(&meta.progress));
+        meta.status = TaskStatus::Running;
+        meta.message = None;
+        meta.updated_at = now_secs();
+        let meta = meta.clone();
+        living.insert(task_id.to_string(), Arc::clone(&progress));
+        drop(living);
+        drop(tasks);
+
+        if let Err(e) = self.save_meta(&meta) {
+            log::error!("save task {} error: {}", task_id, e);
+        }
+        let id = task_id.to_string();
+        let task = meta.task;
+        GLOBAL_RUNTIME.spawn(async move {
+            self.run(id, task, progress).await;
+        });
+        Ok(())
+    }
+
+    async fn run(&self, task_id: String, task: TaskType, progress: Arc<TaskProgress>) {
+        log::info!("task {} start", task_id);
+        let save_interval = match get_config() {
+            Ok(c) => c.task.save_interval_secs,
+            Err(_) => 5,
+        };
+        let mut ticker = tokio::time::interval(Duration::from_secs(save_interval.max(1)));
+        let job = task.execute(&progress);
+        tokio::pin!(job);
+        let result = loop {
+            tokio::select! {
+                r = &mut job => break r,
+                _ = ticker.tick() => self.save_progress(&task_id, &progress),
+            }
+        };
+
+        let (status, message) = match result {
+            Ok(summary) if progress.is_stopped() => (TaskStatus::Stopped, Some(summary)),
+            Ok(summary) => (TaskStatus::Finished, Some(summary)),
+            Err(e) => {
+                log::error!("task {} error: {}", task_id, e);
+                (TaskStatus::Failed, Some(e.to_string()))
+            }
+        };
+        log::info!("task {} {:?}", task_id, status);
+        let finished = self.update_meta(&task_id, |meta| {
+            meta.status = status;
+            meta.progress = progress.snapshot();
+            meta.message = message;
+        });
+        if let Some(meta) = finished {
+            if let Err(e) = self.save_meta(&meta) {
+                log::error!("save task {} error: {}", task_id, e);
+            }
+        }
+        if let Ok(mut living) = self.living.write() {
+            living.remove(&task_id);
+        }
+    }
+
+    fn save_progress(&self, task_id: &str, progress: &TaskProgress) {
+        let meta = self.update_meta(task_id, |meta| meta.progress = progress.snapshot());
+        if let Some(meta) = meta {
+            if let Err(e) = self.save_meta(&meta) {
+                log::error!("save task {} error: {}", task_id, e);
+            }
+        }
+    }
+
+    // 在 tasks 写锁内修改任务并返回副本，由调用方在锁外保存
+    fn update_meta(&self, task_id: &str, update: impl FnOnce(&mut TaskMeta)) -> Option<TaskMeta> {
+        let mut tasks = self.tasks.write().ok()?;
+        let meta = tasks.get_mut(task_id)?;
+        update(meta);
+        meta.updated_at = now_secs();
+        Some(meta.clone())
+    }
+
+    /// 设置停止标记，任务在处理完当前批次后退出
+    pub fn stop(&self, task_id: &str) -> Result<()> {
+        let living = self.living.read().map_err(|e| anyhow!(e.to_string()))?;
+        match living.get(task_id) {
+            Some(p) => {
+                p.stop();
+                Ok(())
+            }
+            None => Err(TaskError::NotRunning(task_id.to_string()).into()),
+        }
+    }
+
+    /// 为全部运行中任务设置停止标记
+    pub fn stop_all(&self) {
+        if let Ok(living) = self.living.read() {
+            living.values().for_each(|p| p.stop());
+        }
+    }
+
+    /// 退出前保存运行中任务的断点。停止全部任务并等待其退出，
+    /// 超时未退出的任务按 stopped 保存当前进度，下次启动可通过 start 继续
+    pub fn checkpoint(&self, timeout: Duration) -> Result<()> {
+        self.stop_all();
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if self
+                .living
+                .read()
+                .map_err(|e| anyhow!(e.to_string()))?
+                .is_empty()
+            {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        let mut tasks = self.tasks.write().map_err(|e| anyhow!(e.to_string()))?;
+        let living = self.living.read().map_err(|e| anyhow!(e.to_string()))?;
+        let mut interrupted = vec![];
+        for (task_id, progress) in living.iter() {
+            if let Some(meta) = tasks.get_mut(task_id) {
+                meta.status = TaskStatus::Stopped;
+                meta.progress = progress.snapshot();
+                meta.message = Some("interrupted by server shutdown".to_string());
+                meta.updated_at = now_secs();
+                interrupted.push(meta.clone());
+            }
+        }
+        drop(living);
+        drop(tasks);
+        for meta in interrupted {
+            self.save_meta(&meta)?;
+            log::warn!("task {} checkpointed before exit", meta.task_id);
+        }
+        Ok(())
+    }
+
+    /// 任务描述及状态，运行中任务返回实时进度
+    pub fn show(&self, task_id: &str) -> Result<TaskMeta> {
+        let mut meta = self
+            .tasks
+            .read()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .get(task_id)
+            .cloned()
+            .ok_or_else(|| TaskError::NotExist(task_id.to_string()))?;
+        if let Some(p) = self.living_snapshots()?.remove(task_id) {
+            meta.progress = p;
+        }
+        Ok(meta)
+    }
+
+    pub fn all(&self) -> Result<Vec<TaskMeta>> {
+        let mut metas = self
+            .tasks
+            .read()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .values()
+            .cloned()
+            .collect::<Vec<TaskMeta>>();
+        let mut snapshots = self.living_snapshots()?;
+        for meta in metas.iter_mut() {
+            if let Some(p) = snapshots.remove(&meta.task_id) {
+                meta.progress = p;
+            }
+        }
+        metas.sort_by_key(|m| m.created_at);
+        Ok(metas)
+    }
+
+    pub fn all_living(&self) -> Result<Vec<TaskMeta>> {
+        let snapshots = self.living_snapshots()?;
+        let tasks = self.tasks.read().map_err(|e| anyhow!(e.to_string()))?;
+        let metas = snapshots
+            .into_iter()
+            .filter_map(|(id, p)| {
+                tasks.get(&id).cloned().map(|mut meta| {
+                    meta.progress = p;
+                    meta
+                })
+            })
+            .collect::<Vec<TaskMeta>>();
+        Ok(metas)
+    }
+
+    // 运行中任务的实时进度，复制后即释放 living 锁
+    fn living_snapshots(&self) -> Result<HashMap<String, ProgressSnapshot>> {
+        let living = self.living.read().map_err(|e| anyhow!(e.to_string()))?;
+        Ok(living
+            .iter()
+            .map(|(id, p)| (id.clone(), p.snapshot()))
+            .collect())
+    }
+
+    pub fn remove(&self, task_ids: &[String]) -> Result<()> {
+        let mut tasks = self.tasks.write().map_err(|e| anyhow!(e.to_string()))?;
+        let living = self.living.read().map_err(|e| anyhow!(e.to_string()))?;
+        if let Some(id) = task_ids.iter().find(|id| living.contains_key(*id)) {
+            return Err(TaskError::Running(id.to_string()).into());
+        }
+        drop(living);
+        let removed = task_ids
+            .iter()
+            .filter(|id| tasks.remove(*id).is_some())
+            .collect::<Vec<&String>>();
+        drop(tasks);
+        for id in removed {
+            let path = self.meta_path(id)?;
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn meta_dir(&self) -> Result<PathBuf> {
+        match &self.meta_dir {
+            Some(d) => Ok(d.clone()),
+            None => Ok(PathBuf::from(get_config()?.task.meta_dir)),
+        }
+    }
+
+    fn meta_path(&self, task_id: &str) -> Result<PathBuf> {
+        Ok(self.meta_dir()?.join(format!("{}.yml", task_id)))
+    }
+
+    fn save_meta(&self, meta: &TaskMeta) -> Result<()> {
+        let path = self.meta_path(&meta.task_id)?;
+        if let Some(p) = path.parent() {
+            fs::create_dir_all(p)?;
+        }
+        struct_to_yml_file(meta, &path.display().to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TaskManager;
+    use crate::{ingest::IngestFolder, tasks::TaskType};
+    use std::{sync::mpsc, time::Duration};
+
+    //cargo test tasks::task_manager::test::test_concurrent_start_show -- --nocapture
+    #[test]
+    fn test_concurrent_start_show() {
+        let meta_dir =
+            std::env::temp_dir().join(format!("embedding_server_tasks_{}", std::process::id()));
+        let manager: &'static TaskManager =
+            Box::leak(Box::new(TaskManager::with_meta_dir(meta_dir.clone())));
+        let ids = (0..4)
+            .map(|i| {
+                let ingest = IngestFolder {
+                    folder: meta_dir.join("not_exist").display().to_string(),
+                    include_regex: None,
+                    exclude_regex: None,
+                    last_modify_filter: None,
+                };
+                manager
+                    .create(format!("task_{}", i), TaskType::Ingest(ingest))
+                    .unwrap()
+            })
+            .collect::<Vec<String>>();
+
+        let (tx, rx) = mpsc::channel();
+        let threads = 8;
+        for n in 0..threads {
+            let ids = ids.clone();
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                for i in 0..200 {
+                    let id = &ids[(n + i) % ids.len()];
+                    if n % 2 == 0 {
+                        let _ = manager.start(id);
+                    } else {
+                        manager.show(id).unwrap();
+                        manager.all().unwrap();
+                        manager.all_living().unwrap();
+                    }
+                }
+                tx.send(()).unwrap();
+            });
+        }
+        for _ in 0..threads {
+            rx.recv_timeout(Duration::from_secs(30))
+                .expect("task manager deadlocked");
+        }
+        manager.checkpoint(Duration::from_secs(10)).unwrap();
+        assert_eq!(manager.all().unwrap().len(), ids.len());
+        let _ = std::fs::remove_dir_all(meta_dir);
+    }
+}
@@ -0,0 +1,105 @@
+use super::task::TaskProgress;
+use crate::{
+    configure::get_config,
+    embedding::embedding_batch,
+    resources::resource_qdrant::{
+        count_points, point_id_from_string, point_id_to_string, scroll_points, upsert_points,
+    },
+};
+use anyhow::{anyhow, Result};
+use qdrant_client::{qdrant::PointStruct, Payload};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskReembed {
+    // 缺省为配置文件中的 collection
+    #[serde(default)]
+    pub collection: Option<String>,
+    // 缺省为 ingest.text_field
+    #[serde(default)]
+    pub text_field: Option<String>,
+    #[serde(default = "TaskReembed::batch_size_default")]
+    pub batch_size: u32,
+}
+
+impl TaskReembed {
+    fn batch_size_default() -> u32 {
+        64
+    }
+
+    /// 分页遍历 collection，以 payload 中的文本重新计算向量后原 id 写回
+    pub async fn execute(&self, progress: &TaskProgress) -> Result<u64> {
+        let config = get_config()?;
+        let collection = self
+            .collection
+            .clone()
+            .unwrap_or(config.qdrant.collection.clone());
+        let text_field = self
+            .text_field
+            .clone()
+            .unwrap_or(config.ingest.text_field.clone());
+
+        progress
+            .total
+            .store(count_points(collection.as_str()).await?, Ordering::SeqCst);
+        let mut offset = progress.checkpoint().map(|c| point_id_from_string(&c));
+        let mut reembedded = 0;
+
+        loop {
+            if progress.is_stopped() {
+                break;
+            }
+            let r = scroll_points(collection.as_str(), offset, self.batch_size, false).await?;
+            // 一页内的文本合并为一次 embedding_batch
+            let mut rows = vec![];
+            for p in r.result {
+                let text = match p.payload.get(&text_field).and_then(|v| v.as_str()) {
+                    Some(t) => t.clone(),
+                    None => {
+                        log::warn!(
+                            "point {} has no text field {}",
+                            point_id_to_string(&p.id),
+                            text_field
+                        );
+                        progress.failed.fetch_add(1, Ordering::SeqCst);
+                        continue;
+                    }
+                };
+                let id = p.id.ok_or_else(|| anyhow!("point without id"))?;
+                rows.push((id, text, p.payload));
+            }
+            let texts = rows
+                .iter()
+                .map(|(_, text, _)| text.clone())
+                .collect::<Vec<String>>();
+            let vectors = if texts.is_empty() {
+                vec![]
+            } else {
+                embedding_batch(&texts).await?
+            };
+            let points = rows
+                .into_iter()
+                .zip(vectors)
+                .map(|((id, _, payload), vector)| {
+                    PointStruct::new(id, vector, Payload::from(payload))
+                })
+                .collect::<Vec<PointStruct>>();
+            let count = points.len() as u64;
+            if !points.is_empty() {
+                upsert_points(collection.as_str(), points).await?;
+            }
+            reembedded += count;
+            progress.processed.fetch_add(count, Ordering::SeqCst);
+
+            match r.next_page_offset {
+                Some(next) => {
+                    progress.set_checkpoint(point_id_to_string(&Some(next.clone())));
+                    offset = Some(next);
+                }
+                None => break,
+            }
+        }
+        Ok(reembedded)
+    }
+}